@@ -0,0 +1,101 @@
+use biscuits::{
+    CompactBoard, FnStrategy, Game, RandomStrategy, Strategy,
+    all_big_zero_or_one_zero_or_big_min_strategy, all_zero_or_big_min_strategy,
+    all_zero_or_prio_min_strategy, fettermania_blackjack_strategy, find_big_min_die,
+    find_zero_point_dice, one_max_strategy, simulate_game,
+};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use std::hint::black_box;
+
+// Every benchmark plays the same seed so numbers are comparable between runs
+const SEED: u64 = 800;
+
+fn bench_strategies(c: &mut Criterion) {
+    let strategies: Vec<(&str, Box<dyn Strategy>)> = vec![
+        (
+            "all_zero_or_big_min",
+            Box::new(FnStrategy(all_zero_or_big_min_strategy)),
+        ),
+        (
+            "all_big_zero_or_one_zero_or_big_min",
+            Box::new(FnStrategy(all_big_zero_or_one_zero_or_big_min_strategy)),
+        ),
+        (
+            "all_zero_or_prio_min",
+            Box::new(FnStrategy(all_zero_or_prio_min_strategy)),
+        ),
+        (
+            "fettermania_blackjack",
+            Box::new(FnStrategy(fettermania_blackjack_strategy)),
+        ),
+        ("one_max", Box::new(FnStrategy(one_max_strategy))),
+        ("random", Box::new(RandomStrategy::new(SEED))),
+    ];
+
+    let mut group = c.benchmark_group("simulate_game");
+    for (name, mut strategy) in strategies {
+        group.bench_function(name, |b| {
+            b.iter(|| simulate_game(&mut strategy, black_box(SEED)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_game(c: &mut Criterion) {
+    let mut rolled = Game::new();
+    rolled.roll_all(&mut SmallRng::seed_from_u64(SEED));
+
+    c.bench_function("roll_all", |b| {
+        let mut rng = SmallRng::seed_from_u64(SEED);
+        let mut game = Game::new();
+        b.iter(|| game.roll_all(&mut rng))
+    });
+
+    c.bench_function("remove_dice/single", |b| {
+        b.iter_batched_ref(
+            || rolled.clone(),
+            |game| game.remove_dice(&mut [7]),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("remove_dice/multi", |b| {
+        b.iter_batched_ref(
+            || rolled.clone(),
+            |game| game.remove_dice(&mut [14, 2, 9, 0, 5]),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_compact(c: &mut Criterion) {
+    let mut game = Game::new();
+    game.roll_all(&mut SmallRng::seed_from_u64(SEED));
+    let dice = game.current_dice().to_vec();
+    let board = CompactBoard::from_dice(&dice).unwrap();
+
+    let mut group = c.benchmark_group("zeros_and_min");
+    group.bench_function("slice", |b| {
+        b.iter(|| {
+            let dice = black_box(dice.as_slice());
+            (find_zero_point_dice(dice), find_big_min_die(dice))
+        })
+    });
+    group.bench_function("compact", |b| {
+        b.iter(|| {
+            let board = black_box(&board);
+            (board.zero_point_dice(), board.min_points_die())
+        })
+    });
+    group.bench_function("compact_from_dice", |b| {
+        b.iter(|| {
+            CompactBoard::from_dice(black_box(&dice)).map(|board| board.all_zero_or_big_min())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_strategies, bench_game, bench_compact);
+criterion_main!(benches);
@@ -0,0 +1,267 @@
+use crate::game::Game;
+use crate::strategy::Strategy;
+use crate::{play, simulate_game};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Head-to-head record of one strategy against another over the same seeds, from the point of
+/// view of the first strategy. Lower scores win.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Comparison {
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    /// Mean of the first strategy's score minus the second's, so negative is better. Zero when no
+    /// games were played.
+    pub avg_difference: f64,
+}
+
+impl Comparison {
+    pub fn games(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Fraction of games the first strategy won outright, or zero when no games were played.
+    pub fn win_rate(&self) -> f64 {
+        per_game(self.wins as f64, self.games())
+    }
+
+    /// Fraction of games the first strategy won, counting a tie as half a win. Unlike
+    /// [`win_rate`](Self::win_rate), the shares of two strategies against each other always add
+    /// up to one, as long as they played at all. With no games it's zero.
+    pub fn win_share(&self) -> f64 {
+        per_game(self.wins as f64 + self.ties as f64 / 2.0, self.games())
+    }
+}
+
+// Spreads `total` over `games`, leaving nothing to spread when there were none
+fn per_game(total: f64, games: u64) -> f64 {
+    if games == 0 {
+        0.0
+    } else {
+        total / games as f64
+    }
+}
+
+/// Plays both strategies on seeds `0..num_simulations`. Each seed seeds its own generator, so both
+/// strategies see identical rolls until their choices leave different dice on the board.
+pub fn compare_strategies<A, B>(a: &mut A, b: &mut B, num_simulations: u64) -> Comparison
+where
+    A: Strategy + ?Sized,
+    B: Strategy + ?Sized,
+{
+    let a_scores: Vec<u16> = (0..num_simulations).map(|i| simulate_game(a, i)).collect();
+    let b_scores: Vec<u16> = (0..num_simulations).map(|i| simulate_game(b, i)).collect();
+
+    compare_scores(&a_scores, &b_scores)
+}
+
+// Compares two strategies' scores on the same seeds, game by game
+fn compare_scores(a_scores: &[u16], b_scores: &[u16]) -> Comparison {
+    let mut comparison = Comparison::default();
+    let mut total_difference: i64 = 0;
+
+    for (&a_points, &b_points) in a_scores.iter().zip(b_scores) {
+        match a_points.cmp(&b_points) {
+            Ordering::Less => comparison.wins += 1,
+            Ordering::Equal => comparison.ties += 1,
+            Ordering::Greater => comparison.losses += 1,
+        }
+        total_difference += a_points as i64 - b_points as i64;
+    }
+
+    comparison.avg_difference = per_game(total_difference as f64, comparison.games());
+    comparison
+}
+
+/// Compares every strategy against every other on seeds `0..num_simulations`, the same way
+/// [`compare_strategies`] does. Entry `[i][j]` is the [win share](Comparison::win_share) of
+/// strategy `i` against strategy `j`, so the diagonal is all one half and `[i][j] + [j][i]` is
+/// always one. With no simulations every entry is zero.
+///
+/// Every strategy plays each seed once and its scores are compared against the rest, so a
+/// strategy that keeps state between games sees the seeds in the same order whichever pair it's
+/// in.
+pub fn win_matrix<N, S: Strategy>(
+    strategies: &mut [(N, S)],
+    num_simulations: u64,
+) -> Vec<Vec<f64>> {
+    win_matrix_on(strategies, &Game::new(), 0..num_simulations)
+}
+
+/// Like [`win_matrix`], but every game starts from the dice `game` started with and follows its
+/// rules, and the strategies play `seeds` instead of starting from zero.
+pub fn win_matrix_on<N, S: Strategy>(
+    strategies: &mut [(N, S)],
+    game: &Game,
+    seeds: Range<u64>,
+) -> Vec<Vec<f64>> {
+    let mut played = game.clone();
+    let scores: Vec<Vec<u16>> = strategies
+        .iter_mut()
+        .map(|(_, strategy)| {
+            seeds
+                .clone()
+                .map(|i| play(strategy, &mut played, &mut SmallRng::seed_from_u64(i)).points)
+                .collect()
+        })
+        .collect();
+
+    scores
+        .iter()
+        .map(|a| {
+            scores
+                .iter()
+                .map(|b| compare_scores(a, b).win_share())
+                .collect()
+        })
+        .collect()
+}
+
+/// Outcome of a game between several players, see [`simulate_match`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    /// Every player's final score, in the order the strategies were given.
+    pub scores: Vec<u16>,
+    /// Index of the player with the lowest score, or `None` if the lowest score was shared.
+    pub winner: Option<usize>,
+}
+
+/// Plays one game of Biscuits between every strategy in `strategies`, each on its own board.
+///
+/// Every player rolls from its own generator seeded with `seed`, the same way
+/// [`compare_strategies`] does, so the players are dealt identical rolls until their choices leave
+/// different dice on the board. That keeps luck out of the result as much as possible, and means
+/// identical strategies always tie.
+pub fn simulate_match<S: Strategy>(strategies: &mut [S], seed: u64) -> MatchResult {
+    let scores: Vec<u16> = strategies
+        .iter_mut()
+        .map(|strategy| simulate_game(strategy, seed))
+        .collect();
+
+    let best = scores.iter().min();
+    let mut leaders = scores
+        .iter()
+        .enumerate()
+        .filter(|(_, points)| Some(*points) == best);
+    let winner = match (leaders.next(), leaders.next()) {
+        (Some((i, _)), None) => Some(i),
+        _ => None,
+    };
+
+    MatchResult { scores, winner }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{
+        FnStrategy, all_zero_or_big_min_strategy, fettermania_blackjack_strategy, one_max_strategy,
+    };
+
+    #[test]
+    fn test_compare_with_itself() {
+        let mut a = FnStrategy(all_zero_or_big_min_strategy);
+        let mut b = FnStrategy(all_zero_or_big_min_strategy);
+
+        let comparison = compare_strategies(&mut a, &mut b, 1000);
+
+        assert_eq!(1000, comparison.ties);
+        assert_eq!(0.0, comparison.win_rate());
+        assert_eq!(0.0, comparison.avg_difference);
+
+        // No games leaves nothing to average rather than NaN
+        let comparison = compare_strategies(&mut a, &mut b, 0);
+        assert_eq!(Comparison::default(), comparison);
+        assert_eq!(0.0, comparison.win_rate());
+        assert_eq!(0.0, comparison.win_share());
+    }
+
+    #[test]
+    fn test_simulate_match() {
+        let mut players = [
+            FnStrategy(all_zero_or_big_min_strategy),
+            FnStrategy(all_zero_or_big_min_strategy),
+        ];
+        for seed in 0..100 {
+            let result = simulate_match(&mut players, seed);
+            assert_eq!(result.scores[0], result.scores[1]);
+            assert_eq!(None, result.winner);
+        }
+
+        // Seed 3 scores 4 for All Zero/Big Min, and only removing the highest die does worse
+        let mut players = [
+            FnStrategy(one_max_strategy),
+            FnStrategy(all_zero_or_big_min_strategy),
+        ];
+        let result = simulate_match(&mut players, 3);
+        assert_eq!(4, result.scores[1]);
+        assert_eq!(Some(1), result.winner);
+
+        assert_eq!(None, simulate_match::<FnStrategy>(&mut [], 3).winner);
+    }
+
+    #[test]
+    fn test_win_matrix() {
+        let mut strategies = crate::all_strategies();
+        let matrix = win_matrix(&mut strategies, 500);
+
+        assert_eq!(strategies.len(), matrix.len());
+        for (i, row) in matrix.iter().enumerate() {
+            assert_eq!(strategies.len(), row.len());
+            assert_eq!(0.5, row[i]);
+            for (j, share) in row.iter().enumerate() {
+                assert!((share + matrix[j][i] - 1.0).abs() < 1e-12);
+            }
+        }
+
+        // Matches comparing the pair on its own
+        let mut a = FnStrategy(fettermania_blackjack_strategy);
+        let mut b = FnStrategy(one_max_strategy);
+        let pair = win_matrix(&mut [("a", a), ("b", b)], 500);
+        assert_eq!(
+            compare_strategies(&mut a, &mut b, 500).win_share(),
+            pair[0][1]
+        );
+
+        // On another game the shares come from games played on that board
+        let game = Game::with_composition(&[(crate::Faces::FOUR, 3)]);
+        let scores = |strategy: &mut FnStrategy| -> Vec<u16> {
+            (0..500)
+                .map(|i| play(strategy, &mut game.clone(), &mut SmallRng::seed_from_u64(i)).points)
+                .collect()
+        };
+        let by_hand = compare_scores(&scores(&mut a), &scores(&mut b));
+        let matrix = win_matrix_on(&mut [("a", a), ("b", b)], &game, 0..500);
+        assert_eq!(by_hand.win_share(), matrix[0][1]);
+
+        // Later seeds give the same shares as comparing the scores on those seeds
+        let scores = |strategy: &mut FnStrategy| -> Vec<u16> {
+            (1000..1500).map(|i| simulate_game(strategy, i)).collect()
+        };
+        let by_hand = compare_scores(&scores(&mut a), &scores(&mut b));
+        let matrix = win_matrix_on(&mut [("a", a), ("b", b)], &Game::new(), 1000..1500);
+        assert_eq!(by_hand.win_share(), matrix[0][1]);
+
+        assert_eq!(
+            vec![vec![0.0; 2]; 2],
+            win_matrix(&mut [("a", a), ("b", b)], 0)
+        );
+    }
+
+    #[test]
+    fn test_compare_strategies() {
+        let mut a = FnStrategy(fettermania_blackjack_strategy);
+        let mut b = FnStrategy(all_zero_or_big_min_strategy);
+
+        let comparison = compare_strategies(&mut a, &mut b, 1000);
+        let reversed = compare_strategies(&mut b, &mut a, 1000);
+
+        assert_eq!(1000, comparison.games());
+        assert_eq!(comparison.wins, reversed.losses);
+        assert_eq!(comparison.avg_difference, -reversed.avg_difference);
+        assert!(comparison.avg_difference < 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::die::Faces;
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Dice composition of a game variant, loaded from a TOML or JSON file.
+///
+/// ```toml
+/// [[dice]]
+/// faces = 6
+/// count = 12
+///
+/// [[dice]]
+/// faces = 20
+/// count = 1
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub dice: Vec<DiceCount>,
+}
+
+/// How many dice of one size a [`GameConfig`] starts with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiceCount {
+    pub faces: u8,
+    pub count: usize,
+}
+
+/// Why a [`GameConfig`] couldn't be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    /// The file isn't valid TOML or JSON, or doesn't describe a game.
+    Parse(String),
+    /// A die size other than the ones in [`GameConfig::SUPPORTED_FACES`].
+    UnsupportedFaces(u8),
+    /// A die size listed with no dice.
+    ZeroCount { faces: u8 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(e) => write!(f, "invalid game config: {}", e),
+            ConfigError::UnsupportedFaces(faces) => {
+                let supported: Vec<String> = GameConfig::SUPPORTED_FACES
+                    .iter()
+                    .map(|faces| faces.value().to_string())
+                    .collect();
+                write!(
+                    f,
+                    "unsupported die with {} faces, expected one of: {}",
+                    faces,
+                    supported.join(", ")
+                )
+            }
+            ConfigError::ZeroCount { faces } => {
+                write!(f, "count for dice with {} faces must be positive", faces)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    pub const SUPPORTED_FACES: [Faces; 6] = Faces::ALL;
+
+    pub fn from_toml(s: &str) -> Result<Self, ConfigError> {
+        let config: GameConfig =
+            toml::from_str(s).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, ConfigError> {
+        let config: GameConfig =
+            serde_json::from_str(s).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that every die has a supported size and a positive count.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for dice in &self.dice {
+            if !Self::SUPPORTED_FACES
+                .iter()
+                .any(|f| f.value() == dice.faces)
+            {
+                return Err(ConfigError::UnsupportedFaces(dice.faces));
+            }
+            if dice.count == 0 {
+                return Err(ConfigError::ZeroCount { faces: dice.faces });
+            }
+        }
+        Ok(())
+    }
+
+    /// The `(faces, count)` pairs for [`Game::with_composition`].
+    pub fn composition(&self) -> Vec<(Faces, usize)> {
+        self.dice
+            .iter()
+            .map(|dice| (Faces::new(dice.faces), dice.count))
+            .collect()
+    }
+}
+
+impl Game {
+    /// Builds a game from a config, after checking it with [`GameConfig::validate`].
+    pub fn from_config(config: &GameConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Game::with_composition(&config.composition()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant() -> GameConfig {
+        GameConfig {
+            dice: vec![
+                DiceCount { faces: 6, count: 4 },
+                DiceCount {
+                    faces: 20,
+                    count: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let config = variant();
+
+        let toml = toml::to_string(&config).unwrap();
+        assert_eq!(config, GameConfig::from_toml(&toml).unwrap());
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(config, GameConfig::from_json(&json).unwrap());
+
+        let game = Game::from_config(&config).unwrap();
+        assert_eq!("6 6 6 6 20 ", game.to_string().lines().last().unwrap());
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = GameConfig::from_toml(
+            "[[dice]]\nfaces = 6\ncount = 4\n\n[[dice]]\nfaces = 20\ncount = 1\n",
+        );
+        assert_eq!(Ok(variant()), config);
+    }
+
+    #[test]
+    fn test_validate() {
+        let err = GameConfig::from_json(r#"{"dice": [{"faces": 7, "count": 2}]}"#).unwrap_err();
+        assert_eq!(ConfigError::UnsupportedFaces(7), err);
+        assert_eq!(
+            "unsupported die with 7 faces, expected one of: 4, 6, 8, 10, 12, 20",
+            err.to_string()
+        );
+
+        let err = GameConfig::from_json(r#"{"dice": [{"faces": 8, "count": 0}]}"#).unwrap_err();
+        assert_eq!(ConfigError::ZeroCount { faces: 8 }, err);
+
+        let err = GameConfig::from_toml("dice = 3").unwrap_err();
+        assert!(matches!(err, ConfigError::Parse(_)));
+    }
+}
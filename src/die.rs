@@ -0,0 +1,425 @@
+use rand::Rng;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// The number of faces on a die.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Faces(u8);
+
+impl Faces {
+    pub const FOUR: Faces = Faces(4);
+    pub const SIX: Faces = Faces(6);
+    pub const EIGHT: Faces = Faces(8);
+    pub const TEN: Faces = Faces(10);
+    pub const TWELVE: Faces = Faces(12);
+    pub const TWENTY: Faces = Faces(20);
+
+    /// Every size with a name above, from smallest to largest.
+    pub const ALL: [Faces; 6] = [
+        Faces::FOUR,
+        Faces::SIX,
+        Faces::EIGHT,
+        Faces::TEN,
+        Faces::TWELVE,
+        Faces::TWENTY,
+    ];
+
+    /// # Panics
+    ///
+    /// Panics if `value` is zero, since a die needs at least one face to roll.
+    pub const fn new(value: u8) -> Self {
+        assert!(value > 0, "a die must have at least one face");
+        Faces(value)
+    }
+
+    /// Iterates over [`Faces::ALL`].
+    pub fn all() -> impl Iterator<Item = Faces> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Whether this is one of the special "big" dice, meaning it has more faces than the d6s that
+    /// make up most of the game. A d4 is smaller than the common die, so it isn't big.
+    pub fn is_big(&self) -> bool {
+        *self > Faces::SIX
+    }
+
+    /// The average points a roll of a die with this many faces is worth, since rolls are uniform
+    /// over `0..value()`.
+    pub fn expected_points(&self) -> f64 {
+        (self.0 - 1) as f64 / 2.0
+    }
+}
+
+/// Which points a roll of a fair die can come up as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RollModel {
+    /// Uniform over `0..faces`, as [`Die::roll`] explains: the top face scores zero, so no roll
+    /// scores the face count.
+    #[default]
+    Exclusive,
+    /// Uniform over `0..=faces`, one more outcome than the die has faces, so a d6 can score 6. Only
+    /// for checking how much results depend on how the dice are modeled.
+    Inclusive,
+}
+
+impl RollModel {
+    /// How many different points a fair die with `faces` can roll under this model.
+    pub fn outcomes(self, faces: Faces) -> u16 {
+        match self {
+            RollModel::Exclusive => faces.value() as u16,
+            RollModel::Inclusive => faces.value() as u16 + 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Die {
+    pub(crate) faces: Faces,
+    pub(crate) points: u8,
+    // Running totals of the weight of every point value, or None for a fair die
+    weights: Option<Arc<[f64]>>,
+}
+
+impl Die {
+    /// Creates a die with an arbitrary number of faces.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `faces` is zero.
+    pub fn with_faces(faces: u8) -> Self {
+        Die {
+            faces: Faces::new(faces),
+            points: faces,
+            weights: None,
+        }
+    }
+
+    /// Creates a loaded die, where `weights[p]` is the relative chance of a roll scoring `p`
+    /// points. A die weighted entirely to zero always rolls zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `faces` is zero, if there isn't exactly one weight per face, or if the weights
+    /// aren't finite, non-negative, and add up to more than zero.
+    pub fn with_weights(faces: u8, weights: &[f64]) -> Self {
+        assert_eq!(
+            faces as usize,
+            weights.len(),
+            "expected one weight per face"
+        );
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "weights must be finite and non-negative"
+        );
+
+        let cumulative: Arc<[f64]> = weights
+            .iter()
+            .scan(0.0, |total, w| {
+                *total += w;
+                Some(*total)
+            })
+            .collect();
+        assert!(
+            cumulative.last().is_some_and(|total| *total > 0.0),
+            "weights must add up to more than zero"
+        );
+
+        Die {
+            weights: Some(cumulative),
+            ..Die::with_faces(faces)
+        }
+    }
+
+    pub fn six() -> Self {
+        Die::with_faces(6)
+    }
+
+    pub fn eight() -> Self {
+        Die::with_faces(8)
+    }
+
+    pub fn ten() -> Self {
+        Die::with_faces(10)
+    }
+
+    pub fn twelve() -> Self {
+        Die::with_faces(12)
+    }
+
+    /// Sets the points showing, as if the die had just rolled them, for building a board to test
+    /// a strategy against. Points past the highest a roll can score are clamped to it, so
+    /// `Die::six().with_points(9)` shows 5.
+    pub fn with_points(mut self, points: u8) -> Self {
+        self.points = points.min(self.faces.value() - 1);
+        self
+    }
+
+    /// Rolls the die. A die scores its faces minus the pips showing, so a d6 showing 1..=6 pips
+    /// scores 5..=0 points and rolling the highest face is worth zero. That makes the range
+    /// `0..faces` rather than `0..=faces`.
+    ///
+    /// Dice from [`Die::with_weights`] roll according to their weights instead.
+    pub fn roll(&mut self, rng: &mut impl Rng) {
+        self.roll_with_model(RollModel::Exclusive, rng);
+    }
+
+    /// Like [`roll`](Self::roll), but a fair die rolls the points `model` allows. Loaded dice
+    /// always roll by their weights.
+    pub fn roll_with_model(&mut self, model: RollModel, rng: &mut impl Rng) {
+        self.points = match (&self.weights, model) {
+            (None, RollModel::Exclusive) => rng.random_range(0..self.faces.value()),
+            (None, RollModel::Inclusive) => rng.random_range(0..=self.faces.value()),
+            (Some(cumulative), _) => roll_weighted(cumulative, rng),
+        };
+    }
+
+    pub fn faces(&self) -> Faces {
+        self.faces
+    }
+
+    /// The points this die scores if it's removed now. Strategies and every statistic the crate
+    /// reports count these, never [pips](Self::pips).
+    pub fn points(&self) -> u8 {
+        self.points
+    }
+
+    /// The pips showing on top, the complement of [`points`](Self::points): a d6 scoring 2 points
+    /// shows 4 pips, and one showing all 6 scores nothing.
+    pub fn pips(&self) -> u8 {
+        self.faces.value() - self.points
+    }
+
+    /// Orders dice from the cheapest to take to the most expensive: fewest points first, and
+    /// between equal points the die with more faces first, since it would cost more to reroll.
+    /// That's the same order [`find_big_min_die`](crate::find_big_min_die) picks from.
+    ///
+    /// Loaded dice aren't told apart from fair ones, so this isn't a full `Ord` on `Die`.
+    pub fn cmp_by_value(&self, other: &Die) -> Ordering {
+        self.points
+            .cmp(&other.points)
+            .then(other.faces.cmp(&self.faces))
+    }
+
+    /// See [`Faces::is_big`].
+    pub fn is_big(&self) -> bool {
+        self.faces.is_big()
+    }
+
+    /// The average points this die is worth after a reroll. That's [`Faces::expected_points`] for a
+    /// fair die, while a loaded die averages over its weights.
+    pub fn expected_points(&self) -> f64 {
+        let Some(cumulative) = &self.weights else {
+            return self.faces.expected_points();
+        };
+
+        let mut previous = 0.0;
+        let mut weighted = 0.0;
+        for (points, total) in cumulative.iter().enumerate() {
+            weighted += points as f64 * (total - previous);
+            previous = *total;
+        }
+        weighted / previous
+    }
+}
+
+/// Prints like `d12`.
+impl std::fmt::Display for Faces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "d{}", self.0)
+    }
+}
+
+/// Prints the faces and then the points showing, like `d6(2)`.
+impl std::fmt::Display for Die {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.faces, self.points)
+    }
+}
+
+// Kept out of line so fair dice, which is nearly all of them, still roll as fast as before
+#[cold]
+fn roll_weighted(cumulative: &[f64], rng: &mut impl Rng) -> u8 {
+    let target = rng.random_range(0.0..cumulative[cumulative.len() - 1]);
+    cumulative.partition_point(|w| *w <= target) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn test_points() {
+        let mut die = Die::six();
+        die.points = 2;
+        assert_eq!(die.points(), 2); // 6 - 4 = 2 points
+
+        die.points = 0;
+        assert_eq!(die.points(), 0); // 6 - 6 = 0 points
+    }
+
+    #[test]
+    fn test_pips() {
+        assert_eq!(4, Die::six().with_points(2).pips()); // 6 - 4 = 2 points
+        assert_eq!(6, Die::six().with_points(0).pips());
+        assert_eq!(1, Die::twelve().with_points(11).pips());
+
+        for faces in Faces::all() {
+            let die = Die::with_faces(faces.value()).with_points(3);
+            assert_eq!(faces.value(), die.points() + die.pips());
+        }
+    }
+
+    #[test]
+    fn test_with_points() {
+        assert_eq!(3, Die::six().with_points(3).points());
+        assert_eq!(0, Die::twelve().with_points(0).points());
+        assert_eq!(11, Die::twelve().with_points(11).points());
+
+        // Out of range points clamp to the highest a roll can score
+        assert_eq!(5, Die::six().with_points(6).points());
+        assert_eq!(19, Die::with_faces(20).with_points(u8::MAX).points());
+    }
+
+    #[test]
+    fn test_roll_range() {
+        let mut rng = SmallRng::seed_from_u64(6);
+        let mut die = Die::six();
+
+        let rolls: Vec<u8> = (0..1000)
+            .map(|_| {
+                die.roll(&mut rng);
+                die.points()
+            })
+            .collect();
+
+        assert_eq!(Some(&0), rolls.iter().min());
+        assert_eq!(Some(&5), rolls.iter().max());
+    }
+
+    #[test]
+    fn test_roll_inclusive() {
+        let mut rng = SmallRng::seed_from_u64(97);
+        let mut die = Die::six();
+
+        let rolls: Vec<u8> = (0..1000)
+            .map(|_| {
+                die.roll_with_model(RollModel::Inclusive, &mut rng);
+                die.points()
+            })
+            .collect();
+
+        assert_eq!(Some(&0), rolls.iter().min());
+        assert_eq!(Some(&6), rolls.iter().max());
+        assert_eq!(7, RollModel::Inclusive.outcomes(Faces::SIX));
+        assert_eq!(6, RollModel::Exclusive.outcomes(Faces::SIX));
+
+        // The model only changes fair dice
+        let mut loaded = Die::with_weights(4, &[0.0, 0.0, 0.0, 1.0]);
+        loaded.roll_with_model(RollModel::Inclusive, &mut rng);
+        assert_eq!(3, loaded.points());
+    }
+
+    #[test]
+    fn test_with_weights() {
+        let mut rng = SmallRng::seed_from_u64(35);
+        let mut zero = Die::with_weights(6, &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let mut high = Die::with_weights(8, &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 3.0]);
+
+        for _ in 0..1000 {
+            zero.roll(&mut rng);
+            assert_eq!(0, zero.points());
+
+            high.roll(&mut rng);
+            assert!((6..8).contains(&high.points()));
+        }
+        assert_eq!(Faces::EIGHT, high.faces());
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must add up to more than zero")]
+    fn test_with_weights_all_zero() {
+        Die::with_weights(4, &[0.0; 4]);
+    }
+
+    #[test]
+    fn test_cmp_by_value() {
+        let mut dice = [
+            Die::six().with_points(2),
+            Die::twelve().with_points(0),
+            Die::eight().with_points(2),
+            Die::six().with_points(0),
+            Die::ten().with_points(5),
+        ];
+        dice.sort_by(Die::cmp_by_value);
+
+        let sorted: Vec<(u8, u8)> = dice.iter().map(|d| (d.points, d.faces.value())).collect();
+        assert_eq!(vec![(0, 12), (0, 6), (2, 8), (2, 6), (5, 10)], sorted);
+        assert_eq!(Ordering::Equal, Die::six().cmp_by_value(&Die::six()));
+    }
+
+    #[test]
+    fn test_all_faces() {
+        assert_eq!(Faces::ALL.len(), Faces::all().count());
+        assert!(Faces::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            vec![4, 6, 8, 10, 12, 20],
+            Faces::all().map(|faces| faces.value()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("d12", Faces::TWELVE.to_string());
+        assert_eq!("d4", Faces::FOUR.to_string());
+        assert_eq!("d6(2)", Die::six().with_points(2).to_string());
+        assert_eq!("d10(0)", Die::ten().with_points(0).to_string());
+
+        let loaded = Die::with_weights(4, &[1.0, 0.0, 0.0, 3.0]).with_points(3);
+        assert_eq!("d4(3)", loaded.to_string());
+    }
+
+    #[test]
+    fn test_is_big() {
+        assert!(!Die::six().is_big());
+        assert!(Die::eight().is_big());
+        assert!(Die::ten().is_big());
+        assert!(Die::twelve().is_big());
+
+        assert!(!Faces::FOUR.is_big());
+        assert!(Faces::TWENTY.is_big());
+    }
+
+    #[test]
+    fn test_expected_points() {
+        for faces in Faces::all() {
+            let total: u32 = (0..faces.value() as u32).sum();
+            assert_eq!(total as f64 / faces.value() as f64, faces.expected_points());
+        }
+
+        assert_eq!(2.5, Faces::SIX.expected_points());
+        assert_eq!(5.5, Die::twelve().expected_points());
+        assert_eq!(0.0, Faces::new(1).expected_points());
+
+        let loaded = Die::with_weights(4, &[1.0, 0.0, 0.0, 3.0]);
+        assert_eq!(2.25, loaded.expected_points());
+        assert_eq!(1.5, loaded.faces().expected_points());
+    }
+
+    #[test]
+    fn test_roll_d20() {
+        let mut rng = SmallRng::seed_from_u64(20);
+        let mut die = Die::with_faces(20);
+        assert_eq!(die.faces().value(), 20);
+
+        for _ in 0..1000 {
+            die.roll(&mut rng);
+            assert!((0..20).contains(&die.points()));
+        }
+    }
+}
@@ -0,0 +1,681 @@
+use crate::die::{Die, Faces, RollModel};
+use crate::strategy::{dice_face_counts, find_max_points_die, find_min_points_die};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// What removing a die adds to the [score](Game::score).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoringMode {
+    /// The points the die rolled, as in the real game.
+    #[default]
+    RolledPoints,
+    /// The die's face count, whatever it rolled, so a d12 always costs 12. Rerolls still go by
+    /// the rolled points, only the score changes.
+    FaceValue,
+}
+
+/// Game state
+///
+/// [`simulate_game`](crate::simulate_game) plays a whole game at once, but a game can also be
+/// driven one round at a time:
+///
+/// ```
+/// use biscuits::{Game, all_zero_or_big_min_strategy};
+/// use rand::SeedableRng;
+/// use rand::rngs::SmallRng;
+///
+/// let mut rng = SmallRng::seed_from_u64(800);
+/// let mut game = Game::new();
+///
+/// while !game.is_over() {
+///     game.roll_all(&mut rng);
+///     let mut indices = all_zero_or_big_min_strategy(game.current_dice());
+///     game.remove_dice(&mut indices);
+/// }
+///
+/// assert_eq!(0, game.score());
+/// ```
+#[derive(Clone)]
+pub struct Game {
+    pub(crate) dice: Vec<Die>,
+    // The dice the game started with, for reset
+    initial: Vec<Die>,
+    score: u16,
+    scoring: ScoringMode,
+    gravy_threshold: u16,
+    roll_model: RollModel,
+}
+
+impl Game {
+    /// Twelve six-sided dice plus one each of an eight, ten, and twelve-sided die.
+    pub const DEFAULT_COMPOSITION: [(Faces, usize); 4] = [
+        (Faces::SIX, 12),
+        (Faces::EIGHT, 1),
+        (Faces::TEN, 1),
+        (Faces::TWELVE, 1),
+    ];
+
+    pub fn new() -> Self {
+        Self::with_composition(&Self::DEFAULT_COMPOSITION)
+    }
+
+    /// Builds a game from `(faces, count)` pairs. Dice are added in the order given, and an empty
+    /// composition produces a game that is already over.
+    pub fn with_composition(counts: &[(Faces, usize)]) -> Self {
+        let mut dice = Vec::with_capacity(counts.iter().map(|(_, count)| count).sum());
+
+        for (faces, count) in counts {
+            for _ in 0..*count {
+                dice.push(Die::with_faces(faces.value()));
+            }
+        }
+
+        Game {
+            initial: dice.clone(),
+            dice,
+            score: 0,
+            scoring: ScoringMode::default(),
+            gravy_threshold: 0,
+            roll_model: RollModel::default(),
+        }
+    }
+
+    /// Scores removed dice by `scoring` instead of their [rolled points](ScoringMode::RolledPoints).
+    pub fn with_scoring(mut self, scoring: ScoringMode) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    pub fn scoring(&self) -> ScoringMode {
+        self.scoring
+    }
+
+    /// Counts every game finishing with at most `gravy_threshold` points as a gravy in the
+    /// summaries of runs on this game, for variants where a strict zero is too rare to compare
+    /// strategies by. The default of 0 only counts perfect games.
+    pub fn with_gravy_threshold(mut self, gravy_threshold: u16) -> Self {
+        self.gravy_threshold = gravy_threshold;
+        self
+    }
+
+    pub fn gravy_threshold(&self) -> u16 {
+        self.gravy_threshold
+    }
+
+    /// Rolls fair dice by `roll_model` instead of the [exclusive](RollModel::Exclusive) default.
+    pub fn with_roll_model(mut self, roll_model: RollModel) -> Self {
+        self.roll_model = roll_model;
+        self
+    }
+
+    pub fn roll_model(&self) -> RollModel {
+        self.roll_model
+    }
+
+    /// Copies the game as it stands, dice, points, and score included, so the copy can be played
+    /// out to see where a choice leads without touching this one. Same as `clone`.
+    pub fn fork(&self) -> Game {
+        self.clone()
+    }
+
+    /// Puts every die back on the board and the score back to zero, reusing the existing
+    /// allocation.
+    pub fn reset(&mut self) {
+        self.dice.clone_from(&self.initial);
+        self.score = 0;
+    }
+
+    /// Rolls every die still on the board.
+    pub fn roll_all(&mut self, rng: &mut impl Rng) {
+        for die in &mut self.dice {
+            die.roll_with_model(self.roll_model, rng);
+        }
+    }
+
+    /// Rerolls only the dice at `indices`, leaving the rest showing the points they already had.
+    /// Dice are rolled in the order given, so the same indices in a different order can roll
+    /// different points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds.
+    pub fn roll_indices(&mut self, indices: &[usize], rng: &mut impl Rng) {
+        for &index in indices {
+            self.dice[index].roll_with_model(self.roll_model, rng);
+        }
+    }
+
+    /// Removes the dice at `indices`, which must be unique, and returns the sum of their points,
+    /// which is also added to the [score](Self::score). `indices` is sorted in place. Under
+    /// [`ScoringMode::FaceValue`] each die counts its face count instead of its points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds. Duplicate indices are a bug in the strategy rather
+    /// than something to recover from, so they panic in debug builds and remove the wrong dice in
+    /// release builds.
+    pub fn remove_dice(&mut self, indices: &mut [usize]) -> u16 {
+        // Removing from the back keeps the remaining indices valid through each swap_remove. Most
+        // strategies only remove one die, which doesn't need sorting at all.
+        if indices.len() > 1 {
+            indices.sort_unstable();
+            debug_assert!(
+                indices.windows(2).all(|pair| pair[0] != pair[1]),
+                "duplicate indices: {:?}",
+                indices
+            );
+        }
+
+        let mut points = 0;
+        for index in indices.iter().rev() {
+            let die = self.dice.swap_remove(*index);
+            points += self.removal_points(&die);
+        }
+        self.score += points;
+        points
+    }
+
+    /// Returns what [`remove_dice`](Self::remove_dice) would add to the score for the same
+    /// `indices`, without removing anything, e.g. to show what a move costs before making it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds or shows up more than once. Unlike `remove_dice`,
+    /// duplicates are checked in release builds too, since the indices here are more likely to
+    /// come from a person than a strategy.
+    pub fn preview_removal(&self, indices: &[usize]) -> u16 {
+        let mut chosen = vec![false; self.dice.len()];
+
+        indices
+            .iter()
+            .map(|&index| {
+                assert!(!chosen[index], "duplicate index: {}", index);
+                chosen[index] = true;
+                self.removal_points(&self.dice[index])
+            })
+            .sum()
+    }
+
+    // What removing `die` adds to the score under this game's scoring mode
+    pub(crate) fn removal_points(&self, die: &Die) -> u16 {
+        match self.scoring {
+            ScoringMode::RolledPoints => die.points() as u16,
+            ScoringMode::FaceValue => die.faces().value() as u16,
+        }
+    }
+
+    /// Points taken off the board since the game started or was last [reset](Self::reset).
+    pub fn score(&self) -> u16 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.dice.is_empty()
+    }
+
+    /// Total points showing on the dice still on the board.
+    pub fn board_points(&self) -> u32 {
+        self.dice.iter().map(|die| die.points() as u32).sum()
+    }
+
+    /// Most points the dice still on the board could show after a reroll.
+    pub fn board_potential(&self) -> u32 {
+        self.dice
+            .iter()
+            .map(|die| self.roll_model.outcomes(die.faces) as u32 - 1)
+            .sum()
+    }
+
+    /// How many dice of each kind are still on the board.
+    pub fn face_counts(&self) -> BTreeMap<Faces, usize> {
+        dice_face_counts(&self.dice)
+    }
+
+    /// The die showing the fewest points, or `None` once the board is empty. Ties go to the first
+    /// of the tied dice in [`current_dice`](Self::current_dice).
+    pub fn min_points_die(&self) -> Option<&Die> {
+        find_min_points_die(&self.dice).map(|i| &self.dice[i])
+    }
+
+    /// The die showing the most points, or `None` once the board is empty. Ties go to the first
+    /// of the tied dice in [`current_dice`](Self::current_dice).
+    pub fn max_points_die(&self) -> Option<&Die> {
+        find_max_points_die(&self.dice).map(|i| &self.dice[i])
+    }
+
+    /// Whether every die on the board shows more than `threshold` points, so whatever gets removed
+    /// this round costs more than that. An empty board isn't stuck.
+    pub fn is_stuck(&self, threshold: u8) -> bool {
+        self.min_points_die()
+            .is_some_and(|die| die.points() > threshold)
+    }
+
+    /// The dice still on the board, with the points from the latest roll.
+    pub fn current_dice(&self) -> &[Die] {
+        &self.dice
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Prints the points on every die, then their faces underneath. The alternate form `{:#}` marks
+/// the faces of [big](Die::is_big) dice with a `*`.
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for die in self.dice.iter() {
+            write!(f, "{} ", die.points)?;
+        }
+        writeln!(f)?;
+        for die in self.dice.iter() {
+            if f.alternate() && die.is_big() {
+                write!(f, "{}* ", die.faces.value())?;
+            } else {
+                write!(f, "{} ", die.faces.value())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn test_default_composition() {
+        let game = Game::new();
+        assert_eq!(game.dice.len(), 15);
+        assert_eq!(
+            "6 6 6 6 6 6 6 6 6 6 6 6 8 10 12 ",
+            game.to_string().lines().last().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_alternate_display() {
+        let game = Game::new();
+        let alternate = format!("{:#}", game);
+
+        assert_eq!(3, alternate.matches('*').count());
+        assert!(alternate.ends_with("6 6 8* 10* 12* "));
+        assert_eq!(game.to_string(), alternate.replace('*', ""));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut rng = SmallRng::seed_from_u64(24);
+        let mut game = Game::new();
+        game.roll_all(&mut rng);
+        game.remove_dice(&mut [0, 3, 14]);
+
+        game.reset();
+        assert_eq!(Game::new().to_string(), game.to_string());
+        assert_eq!(0, game.score());
+
+        let mut game = Game::with_composition(&[(Faces::TEN, 2)]);
+        game.remove_dice(&mut [0, 1]);
+        assert!(game.is_over());
+
+        game.reset();
+        assert_eq!("10 10 \n10 10 ", game.to_string());
+    }
+
+    #[test]
+    fn test_roll_indices() {
+        let mut rng = SmallRng::seed_from_u64(24);
+        let mut game = Game::new();
+        game.roll_all(&mut rng);
+        let points = |game: &Game| -> Vec<u8> { game.dice.iter().map(|d| d.points()).collect() };
+        let mut before = points(&game);
+
+        game.roll_indices(&[], &mut rng);
+        assert_eq!(before, points(&game));
+
+        // Only the d12 is rolled, with the same draw a lone d12 would get
+        let mut expected = Die::twelve();
+        expected.roll(&mut rng.clone());
+        game.roll_indices(&[14], &mut rng);
+
+        before[14] = expected.points();
+        assert_eq!(before, points(&game));
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut rng = SmallRng::seed_from_u64(24);
+        let mut game = Game::new();
+        game.roll_all(&mut rng);
+        game.remove_dice(&mut [0, 3]);
+        let before = game.to_string();
+
+        let mut fork = game.fork();
+        assert_eq!(before, fork.to_string());
+        assert_eq!(game.score(), fork.score());
+
+        fork.roll_all(&mut rng);
+        fork.remove_dice(&mut [0, 1, 2]);
+        assert_eq!(before, game.to_string());
+        assert_eq!(13, game.current_dice().len());
+        assert_eq!(10, fork.current_dice().len());
+
+        // Forks reset to the dice the game started with, not the board they were forked from
+        fork.reset();
+        assert_eq!(Game::new().to_string(), fork.to_string());
+    }
+
+    #[test]
+    fn test_face_counts() {
+        let mut game = Game::new();
+        assert_eq!(
+            BTreeMap::from([
+                (Faces::SIX, 12),
+                (Faces::EIGHT, 1),
+                (Faces::TEN, 1),
+                (Faces::TWELVE, 1),
+            ]),
+            game.face_counts()
+        );
+
+        // Kinds that run out drop out of the counts
+        game.remove_dice(&mut [0, 1, 12]);
+        assert_eq!(
+            BTreeMap::from([(Faces::SIX, 10), (Faces::TEN, 1), (Faces::TWELVE, 1)]),
+            game.face_counts()
+        );
+
+        game.reset();
+        game.remove_dice(&mut (0..15).collect::<Vec<_>>());
+        assert!(game.face_counts().is_empty());
+
+        // Sizes outside Faces::ALL get counted too, so the big ones add up like an is_big count
+        let dice = vec![
+            Die::with_faces(4),
+            Die::six(),
+            Die::with_faces(7),
+            Die::twelve(),
+            Die::twelve(),
+            Die::with_faces(20),
+            Die::with_faces(100),
+        ];
+        let counts = dice_face_counts(&dice);
+        assert_eq!(1, counts[&Faces::new(7)]);
+        assert_eq!(2, counts[&Faces::TWELVE]);
+        let big: usize = counts
+            .iter()
+            .filter(|(faces, _)| faces.is_big())
+            .map(|(_, count)| count)
+            .sum();
+        assert_eq!(5, big);
+        assert_eq!(dice.iter().filter(|die| die.is_big()).count(), big);
+        assert!(dice_face_counts(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_with_composition() {
+        let game = Game::with_composition(&[(Faces::SIX, 3), (Faces::TWELVE, 1)]);
+        assert_eq!(game.dice.len(), 4);
+        assert_eq!("6 6 6 12 ", game.to_string().lines().last().unwrap());
+
+        let game = Game::with_composition(&[]);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn test_manual_rounds() {
+        let mut rng = SmallRng::seed_from_u64(800);
+        let mut game = Game::with_composition(&[(Faces::SIX, 2), (Faces::TWELVE, 1)]);
+        let mut total_points = 0;
+
+        game.roll_all(&mut rng);
+        let rolled: Vec<u16> = game
+            .current_dice()
+            .iter()
+            .map(|d| d.points() as u16)
+            .collect();
+        total_points += game.remove_dice(&mut [0]);
+        assert_eq!(2, game.current_dice().len());
+
+        game.roll_all(&mut rng);
+        let rerolled: Vec<u16> = game
+            .current_dice()
+            .iter()
+            .map(|d| d.points() as u16)
+            .collect();
+        total_points += game.remove_dice(&mut [0, 1]);
+
+        assert!(game.is_over());
+        assert_eq!(rolled[0] + rerolled[0] + rerolled[1], total_points);
+        assert_eq!(total_points, game.score());
+    }
+
+    #[test]
+    fn test_score() {
+        let mut game = Game::new();
+        game.dice = vec![
+            Die::six().with_points(3),
+            Die::eight().with_points(1),
+            Die::ten().with_points(5),
+            Die::twelve().with_points(2),
+        ];
+
+        game.remove_dice(&mut [2]);
+        assert_eq!(5, game.score());
+        game.remove_dice(&mut []);
+        assert_eq!(5, game.score());
+        game.remove_dice(&mut [0, 2]);
+        assert_eq!(10, game.score());
+
+        // Twenty d20s showing 19 is more than a u8 can hold
+        let mut game = Game::with_composition(&[(Faces::TWENTY, 20)]);
+        for die in &mut game.dice {
+            die.points = 19;
+        }
+        let mut all: Vec<usize> = (0..20).collect();
+        assert_eq!(380, game.remove_dice(&mut all));
+        assert_eq!(380, game.score());
+    }
+
+    #[test]
+    fn test_face_value_scoring() {
+        assert_eq!(ScoringMode::RolledPoints, Game::new().scoring());
+
+        let mut game = Game::new().with_scoring(ScoringMode::FaceValue);
+        for points in 0..12 {
+            game.dice = vec![Die::six().with_points(0), Die::twelve().with_points(points)];
+            assert_eq!(12, game.remove_dice(&mut [1]));
+        }
+        assert_eq!(12 * 12, game.score());
+
+        // Reset keeps the scoring mode
+        game.reset();
+        assert_eq!(ScoringMode::FaceValue, game.scoring());
+        assert_eq!(
+            12 * 6 + 8 + 10 + 12,
+            game.remove_dice(&mut (0..15).collect::<Vec<_>>())
+        );
+    }
+
+    #[test]
+    fn test_board_points() {
+        let mut game = Game::new();
+        assert_eq!(12 * 5 + 7 + 9 + 11, game.board_potential());
+
+        game.dice = vec![
+            Die::six().with_points(3),
+            Die::eight().with_points(0),
+            Die::ten().with_points(5),
+            Die::twelve().with_points(2),
+        ];
+
+        assert_eq!(10, game.board_points());
+        assert_eq!(5 + 7 + 9 + 11, game.board_potential());
+
+        game.remove_dice(&mut [2]);
+        assert_eq!(5, game.board_points());
+        assert_eq!(5 + 7 + 11, game.board_potential());
+    }
+
+    #[test]
+    fn test_min_max_points_die() {
+        let mut game = Game::new();
+        game.dice = vec![
+            Die::six().with_points(3),
+            Die::eight().with_points(1),
+            Die::ten().with_points(5),
+            Die::twelve().with_points(1),
+            Die::twelve().with_points(5),
+        ];
+
+        let min = game.min_points_die().unwrap();
+        assert_eq!((8, 1), (min.faces().value(), min.points()));
+        let max = game.max_points_die().unwrap();
+        assert_eq!((10, 5), (max.faces().value(), max.points()));
+
+        game.remove_dice(&mut [0, 1, 2, 3, 4]);
+        assert!(game.min_points_die().is_none());
+        assert!(game.max_points_die().is_none());
+    }
+
+    #[test]
+    fn test_is_stuck() {
+        let mut game = Game::new();
+        game.dice = vec![
+            Die::six().with_points(4),
+            Die::eight().with_points(6),
+            Die::twelve().with_points(3),
+        ];
+        assert!(game.is_stuck(2));
+        assert!(!game.is_stuck(3));
+
+        game.dice.push(Die::ten().with_points(0));
+        assert!(!game.is_stuck(0));
+
+        game.remove_dice(&mut [0, 1, 2, 3]);
+        assert!(!game.is_stuck(0));
+    }
+
+    #[test]
+    fn test_game_remove_dice() {
+        let mut game = Game::new();
+        game.dice = vec![
+            Die::six().with_points(3),
+            Die::eight().with_points(1),
+            Die::ten().with_points(5),
+            Die::twelve().with_points(2),
+        ];
+
+        let points = game.remove_dice(&mut [1, 3]);
+        assert_eq!(points, 3); // 1 + 2 = 3 points
+        assert_eq!(game.dice.len(), 2); // Should have 2 dice left
+        assert_eq!(game.dice[0].points, 3); // First die should remain
+        assert_eq!(game.dice[1].points, 5); // Third die should remain
+    }
+
+    #[test]
+    fn test_preview_removal() {
+        let mut rng = SmallRng::seed_from_u64(24);
+        let mut game = Game::new();
+        game.roll_all(&mut rng);
+
+        let mut indices = vec![14, 2, 7];
+        let preview = game.preview_removal(&indices);
+        assert_eq!(15, game.current_dice().len());
+        assert_eq!(0, game.score());
+        assert_eq!(preview, game.remove_dice(&mut indices));
+
+        let mut game = Game::new().with_scoring(ScoringMode::FaceValue);
+        assert_eq!(6 + 12, game.preview_removal(&[3, 14]));
+        assert_eq!(0, game.preview_removal(&[]));
+        assert_eq!(6 + 12, game.remove_dice(&mut [3, 14]));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate index: 3")]
+    fn test_preview_removal_duplicates() {
+        Game::new().preview_removal(&[3, 1, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_preview_removal_out_of_bounds() {
+        Game::new().preview_removal(&[15]);
+    }
+
+    #[test]
+    fn test_game_remove_dice_any_order() {
+        let dice = vec![
+            Die::six().with_points(3),
+            Die::eight().with_points(1),
+            Die::ten().with_points(5),
+            Die::twelve().with_points(2),
+        ];
+        let mut game = Game::new();
+
+        game.dice = dice.clone();
+        assert_eq!(5, game.remove_dice(&mut [2]));
+        assert_eq!("3 1 2 ", game.to_string().lines().next().unwrap());
+
+        game.dice = dice.clone();
+        let mut indices = [3, 0, 2];
+        assert_eq!(10, game.remove_dice(&mut indices));
+        assert_eq!([0, 2, 3], indices);
+        assert_eq!("1 ", game.to_string().lines().next().unwrap());
+    }
+
+    // Up to a full game's worth of dice, each with any points it could roll
+    fn arb_dice() -> impl Strategy<Value = Vec<Die>> {
+        let die = prop::sample::select(Faces::ALL.to_vec()).prop_flat_map(|faces| {
+            (0..faces.value())
+                .prop_map(move |points| Die::with_faces(faces.value()).with_points(points))
+        });
+        prop::collection::vec(die, 0..=15)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_remove_dice_conserves_dice(
+            (dice, indices) in arb_dice().prop_flat_map(|dice| {
+                let len = dice.len();
+                let subset = prop::sample::subsequence((0..len).collect::<Vec<_>>(), 0..=len);
+                (Just(dice), subset.prop_shuffle())
+            })
+        ) {
+            let mut game = Game::new();
+            game.dice = dice.clone();
+
+            let expected: u16 = indices.iter().map(|&i| dice[i].points() as u16).sum();
+            let mut kept: Vec<(u8, u8)> = (0..dice.len())
+                .filter(|i| !indices.contains(i))
+                .map(|i| (dice[i].faces().value(), dice[i].points()))
+                .collect();
+
+            prop_assert_eq!(expected, game.remove_dice(&mut indices.clone()));
+            prop_assert_eq!(dice.len() - indices.len(), game.dice.len());
+
+            // The rest of the dice are still there, in some order
+            let mut left: Vec<(u8, u8)> = game
+                .dice
+                .iter()
+                .map(|d| (d.faces().value(), d.points()))
+                .collect();
+            kept.sort_unstable();
+            left.sort_unstable();
+            prop_assert_eq!(kept, left);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate indices")]
+    #[cfg(debug_assertions)]
+    fn test_remove_dice_duplicates() {
+        Game::new().remove_dice(&mut [3, 1, 3]);
+    }
+}
@@ -0,0 +1,1610 @@
+//! Simulation engine for [Biscuits](https://gluebunnygames.com/products/biscuits-a-dice-game).
+//!
+//! A game starts with twelve six-sided dice plus one each of an eight, ten, and twelve-sided die.
+//! Every round all remaining dice are rolled and a [`Strategy`] picks which ones to remove, adding
+//! their points to the total. The game is over once every die has been removed, and the goal is
+//! to finish with as few points as possible.
+//!
+//! Running a single game from another crate:
+//!
+//! ```
+//! use biscuits::{FnStrategy, all_zero_or_big_min_strategy, simulate_game};
+//!
+//! let points = simulate_game(&mut FnStrategy(all_zero_or_big_min_strategy), 800);
+//! assert_eq!(0, points);
+//! ```
+
+mod compact;
+mod compare;
+#[cfg(feature = "serde")]
+mod config;
+mod die;
+mod game;
+mod optimal;
+mod oracle;
+mod states;
+mod strategy;
+mod summary;
+mod trace;
+
+pub use compact::CompactBoard;
+pub use compare::{
+    Comparison, MatchResult, compare_strategies, simulate_match, win_matrix, win_matrix_on,
+};
+#[cfg(feature = "serde")]
+pub use config::{ConfigError, DiceCount, GameConfig};
+pub use die::{Die, Faces, RollModel};
+pub use game::{Game, ScoringMode};
+pub use optimal::OptimalStrategy;
+pub use oracle::{MAX_ORACLE_DICE, average_regret, best_achievable_score, gravy_probability};
+pub use states::{DiceStates, dice_states};
+pub use strategy::*;
+pub use summary::{Moments, SimulationSummary, sample_size_for, score_value};
+pub use trace::{
+    GameTrace, RoundRecord, replay_game, replay_game_on, simulate_game_traced,
+    simulate_game_traced_on,
+};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::any::Any;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use summary::Totals;
+
+/// After this many rounds in a row where the strategy removes nothing, the lowest die is removed
+/// for it so the game can't go on forever.
+pub const MAX_CONSECUTIVE_PASSES: u32 = 100;
+
+// Asks the strategy which dice to remove. An empty selection passes, rerolling everything, until
+// the strategy has passed too many times in a row and the lowest die is taken instead.
+pub(crate) fn select_dice<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    dice: &[Die],
+    passes: &mut u32,
+) -> Vec<usize> {
+    let indices = strategy.select(dice);
+    if !indices.is_empty() {
+        *passes = 0;
+        return indices;
+    }
+
+    if *passes < MAX_CONSECUTIVE_PASSES {
+        *passes += 1;
+        return indices;
+    }
+
+    *passes = 0;
+    find_big_min_die(dice).into_iter().collect()
+}
+
+/// How a single game went.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameResult {
+    /// The final score.
+    pub points: u16,
+    /// How many times the dice were rolled, counting rounds where the strategy passed.
+    pub rounds: u32,
+}
+
+impl GameResult {
+    /// Whether the game was a gravy.
+    pub fn outcome(&self) -> GameOutcome {
+        GameOutcome::from_points(self.points)
+    }
+}
+
+/// A final score split into the perfect games and everything else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    /// Every die was removed without scoring a single point.
+    Gravy,
+    /// The game ended with this many points, never zero.
+    Scored(u16),
+}
+
+impl GameOutcome {
+    pub fn from_points(points: u16) -> Self {
+        match points {
+            0 => GameOutcome::Gravy,
+            points => GameOutcome::Scored(points),
+        }
+    }
+
+    /// The final score, zero for a gravy.
+    pub fn points(&self) -> u16 {
+        match self {
+            GameOutcome::Gravy => 0,
+            GameOutcome::Scored(points) => *points,
+        }
+    }
+}
+
+pub fn simulate_game<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> u16 {
+    simulate_game_result(strategy, seed).points
+}
+
+/// Like [`simulate_game`], but also reports how many rounds the game took.
+pub fn simulate_game_result<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> GameResult {
+    play(
+        strategy,
+        &mut Game::new(),
+        &mut SmallRng::seed_from_u64(seed),
+    )
+}
+
+/// Like [`simulate_game`], but tells gravies apart from scored games.
+pub fn simulate_game_outcome<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> GameOutcome {
+    simulate_game_result(strategy, seed).outcome()
+}
+
+/// Like [`simulate_game`], but rolls the dice with the generator `make_rng` builds from `seed`.
+///
+/// Different generators roll different dice for the same seed, so scores (and which seeds are
+/// gravies) are only comparable between runs that use the same one.
+pub fn simulate_game_with_rng<S, R, F>(strategy: &mut S, seed: u64, make_rng: F) -> u16
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnOnce(u64) -> R,
+{
+    play(strategy, &mut Game::new(), &mut make_rng(seed)).points
+}
+
+/// Like [`simulate_game`], but rolls with ChaCha8 instead of [`SmallRng`].
+///
+/// `SmallRng` is free to change algorithm between `rand` releases, which would change every score
+/// along with which seeds are gravies. ChaCha8's output for a seed is fixed, so results from this
+/// only change if `rand` changes how it turns that output into a roll within a die's range. It's
+/// a bit slower, so it's meant for results that need to be reproduced later rather than sweeps.
+#[cfg(feature = "stable-rng")]
+pub fn simulate_game_stable<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> u16 {
+    simulate_game_with_rng(strategy, seed, rand_chacha::ChaCha8Rng::seed_from_u64)
+}
+
+/// Like [`run_simulations_range`], but rolls with ChaCha8. See [`simulate_game_stable`].
+#[cfg(feature = "stable-rng")]
+pub fn run_simulations_stable<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    seeds: Range<u64>,
+) -> SimulationSummary {
+    run_simulations_with_rng(strategy, seeds, rand_chacha::ChaCha8Rng::seed_from_u64)
+}
+
+/// Like [`simulate_game`], but gives up with `None` as soon as the total goes over `cap`.
+///
+/// Every round up to that point rolls exactly the same dice as the full game would.
+pub fn simulate_game_capped<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    seed: u64,
+    cap: u16,
+) -> Option<u16> {
+    play_capped(
+        strategy,
+        &mut Game::new(),
+        &mut SmallRng::seed_from_u64(seed),
+        cap,
+    )
+    .map(|result| result.points)
+}
+
+/// Plays a game where the dice can only be rolled `max_rounds` times. Once the last roll has been
+/// removed from, every die still on the board scores the points it's showing and the game ends.
+///
+/// Every round up to the limit rolls exactly the same dice as [`simulate_game`] would, so a limit
+/// the game never reaches doesn't change the score. With a limit of zero the board is still rolled
+/// once, since dice that were never rolled have no points to score, and all of it scores.
+pub fn simulate_game_limited<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    seed: u64,
+    max_rounds: u32,
+) -> u16 {
+    simulate_game_limited_on(strategy, &Game::new(), seed, max_rounds)
+}
+
+/// Like [`simulate_game_limited`], but the game starts from the dice `game` started with and
+/// follows its rules.
+pub fn simulate_game_limited_on<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seed: u64,
+    max_rounds: u32,
+) -> u16 {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut game = game.clone();
+    game.reset();
+    let mut passes = 0;
+    strategy.on_new_game();
+
+    for _ in 0..max_rounds {
+        if game.is_over() {
+            break;
+        }
+        play_round(strategy, &mut game, &mut rng, &mut passes, |_, _| {});
+    }
+
+    if max_rounds == 0 {
+        game.roll_all(&mut rng);
+    }
+    let mut rest: Vec<usize> = (0..game.dice.len()).collect();
+    game.remove_dice(&mut rest);
+    game.score()
+}
+
+/// Plays a game where the board can't be emptied with points on it: the last die has to roll a
+/// zero to come off, so the game only ends on a zero.
+///
+/// Whenever the strategy picks every die left and any of them is showing points, the one showing
+/// the most is put back for another roll. For strategies that take several dice at once, that
+/// means a final sweep of the board leaves its worst die behind rather than being refused
+/// outright, and the dice around it still come off.
+pub fn simulate_game_keep_one<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> u16 {
+    simulate_game_keep_one_on(strategy, &Game::new(), seed)
+}
+
+/// Like [`simulate_game_keep_one`], but the game starts from the dice `game` started with and
+/// follows its rules.
+pub fn simulate_game_keep_one_on<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seed: u64,
+) -> u16 {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    // The rule goes between the strategy and the board rather than wrapping the strategy, since a
+    // strategy that had its pick emptied would count as passing and get its last die forced off
+    play_adjusted(strategy, &mut game.clone(), &mut rng, u16::MAX, keep_one)
+        .unwrap()
+        .points
+}
+
+// Takes the highest die showing points out of `indices` if they would clear the board
+fn keep_one(dice: &[Die], indices: &mut Vec<usize>) {
+    if indices.len() < dice.len() {
+        return;
+    }
+
+    let highest = indices
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| dice[**i].points() > 0)
+        .min_by_key(|(_, i)| Reverse(dice[**i].points()))
+        .map(|(position, _)| position);
+    if let Some(position) = highest {
+        indices.remove(position);
+    }
+}
+
+// Resets `game` and plays it to the end. Sweeps reuse one game so they don't allocate new dice
+// for every seed.
+fn play<S, R>(strategy: &mut S, game: &mut Game, rng: &mut R) -> GameResult
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+{
+    // The total can't go over the cap without overflowing first
+    play_capped(strategy, game, rng, u16::MAX).unwrap()
+}
+
+fn play_capped<S, R>(strategy: &mut S, game: &mut Game, rng: &mut R, cap: u16) -> Option<GameResult>
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+{
+    play_adjusted(strategy, game, rng, cap, |_, _| {})
+}
+
+// The game loop behind play, with `adjust` applied to every round's pick like in play_round
+fn play_adjusted<S, R, F>(
+    strategy: &mut S,
+    game: &mut Game,
+    rng: &mut R,
+    cap: u16,
+    mut adjust: F,
+) -> Option<GameResult>
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnMut(&[Die], &mut Vec<usize>),
+{
+    game.reset();
+    strategy.on_new_game();
+    let mut passes = 0;
+    let mut rounds = 0;
+
+    while !game.is_over() {
+        rounds += 1;
+        play_round(strategy, game, rng, &mut passes, &mut adjust);
+
+        if game.score() > cap {
+            return None;
+        }
+    }
+
+    Some(GameResult {
+        points: game.score(),
+        rounds,
+    })
+}
+
+// Rolls the board, asks the strategy which dice to take, lets `adjust` change that for variant
+// rules, and takes them. Every game loop is made of these.
+fn play_round<S, R, F>(strategy: &mut S, game: &mut Game, rng: &mut R, passes: &mut u32, adjust: F)
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnOnce(&[Die], &mut Vec<usize>),
+{
+    game.roll_all(rng);
+    let mut indices = select_dice(strategy, &game.dice, passes);
+    adjust(&game.dice, &mut indices);
+    game.remove_dice(&mut indices);
+}
+
+// Plays every seed in `seeds` on `game`, tallying the final scores and adding the games played to
+// `progress` every so often
+fn tally<S, R, F>(
+    strategy: &mut S,
+    game: &mut Game,
+    seeds: Range<u64>,
+    mut make_rng: F,
+    progress: Option<&AtomicU64>,
+) -> Totals
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnMut(u64) -> R,
+{
+    let mut totals = Totals::with_gravy_threshold(game.gravy_threshold());
+    let mut unreported = 0;
+
+    play_each(strategy, game, seeds, &mut make_rng, |i, result| {
+        totals.add(i, result);
+
+        unreported += 1;
+        if unreported == PROGRESS_INTERVAL {
+            report_progress(progress, &mut unreported);
+        }
+    });
+    report_progress(progress, &mut unreported);
+
+    totals
+}
+
+// Plays every seed in `seeds` on `game` in order, handing each result to `f` as soon as it's played
+fn play_each<S, R, F>(
+    strategy: &mut S,
+    game: &mut Game,
+    seeds: Range<u64>,
+    mut make_rng: F,
+    mut f: impl FnMut(u64, GameResult),
+) where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnMut(u64) -> R,
+{
+    for i in seeds {
+        f(i, play(strategy, game, &mut make_rng(i)));
+    }
+}
+
+// Games are counted locally and added to the shared counter in batches, so parallel jobs don't
+// fight over it after every game
+const PROGRESS_INTERVAL: u64 = 1024;
+
+fn report_progress(progress: Option<&AtomicU64>, unreported: &mut u64) {
+    if let Some(progress) = progress {
+        progress.fetch_add(*unreported, Ordering::Relaxed);
+    }
+    *unreported = 0;
+}
+
+pub fn run_simulations<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    num_simulations: u64,
+) -> SimulationSummary {
+    run_simulations_range(strategy, 0..num_simulations)
+}
+
+/// Plays every seed in `seeds` in order, calling `callback` with the seed and final score of each
+/// game as soon as it's played, instead of summarizing them at the end.
+///
+/// Scores match [`simulate_game`] for the same seeds, and [`run_simulations`] tallies the same
+/// games, so this can feed a live chart, a dump, or a progress bar alongside a summary.
+pub fn run_simulations_with<S, F>(strategy: &mut S, seeds: Range<u64>, mut callback: F)
+where
+    S: Strategy + ?Sized,
+    F: FnMut(u64, u16),
+{
+    play_each(
+        strategy,
+        &mut Game::new(),
+        seeds,
+        SmallRng::seed_from_u64,
+        |i, result| callback(i, result.points),
+    );
+}
+
+/// Like [`run_simulations`], but plays an arbitrary range of seeds.
+pub fn run_simulations_range<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    seeds: Range<u64>,
+) -> SimulationSummary {
+    run_simulations_with_rng(strategy, seeds, SmallRng::seed_from_u64)
+}
+
+/// Like [`run_simulations_range`], but every game rolls with the generator `make_rng` builds from
+/// its seed. See [`simulate_game_with_rng`].
+pub fn run_simulations_with_rng<S, R, F>(
+    strategy: &mut S,
+    seeds: Range<u64>,
+    make_rng: F,
+) -> SimulationSummary
+where
+    S: Strategy + ?Sized,
+    R: Rng,
+    F: FnMut(u64) -> R,
+{
+    let start = Instant::now();
+    tally(strategy, &mut Game::new(), seeds, make_rng, None).finish(start.elapsed())
+}
+
+/// Like [`run_simulations`], but every game keeps rolling from one generator seeded with `seed`,
+/// instead of seeding a new one per game.
+///
+/// That saves setting up a generator for every game, which matters for small games and long
+/// sweeps, but a game can no longer be replayed on its own: it depends on every game before it.
+/// The summary's `best_seed` and `worst_seed` are the game's position in the run rather than a
+/// seed for [`simulate_game`].
+pub fn run_simulations_fast<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    num_simulations: u64,
+    seed: u64,
+) -> SimulationSummary {
+    let start = Instant::now();
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut game = Game::new();
+    let mut totals = Totals::new();
+
+    for i in 0..num_simulations {
+        totals.add(i, play(strategy, &mut game, &mut rng));
+    }
+
+    totals.finish(start.elapsed())
+}
+
+/// Like [`run_simulations_range`], but every game starts from the dice `game` started with instead
+/// of the default composition.
+pub fn run_simulations_on<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+) -> SimulationSummary {
+    run_simulations_on_with_progress(strategy, game, seeds, &AtomicU64::new(0))
+}
+
+/// Like [`run_simulations_on`], but adds the number of games played to `progress` as it goes, so
+/// another thread can watch a long run. The counter is only updated every so often, and is
+/// guaranteed to have every game added once this returns.
+pub fn run_simulations_on_with_progress<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> SimulationSummary {
+    let start = Instant::now();
+    let mut game = game.clone();
+    tally(
+        strategy,
+        &mut game,
+        seeds,
+        SmallRng::seed_from_u64,
+        Some(progress),
+    )
+    .finish(start.elapsed())
+}
+
+/// A game that panicked partway through [`run_simulations_on_catching`].
+#[derive(Debug)]
+pub struct GamePanic {
+    /// The seed of the game that panicked, to replay it.
+    pub seed: u64,
+    /// What the game panicked with, to pass on to [`std::panic::resume_unwind`].
+    pub payload: Box<dyn Any + Send>,
+}
+
+impl GamePanic {
+    /// The panic message, if the game panicked with a string like `panic!` does.
+    pub fn message(&self) -> Option<&str> {
+        match self.payload.downcast_ref::<&str>() {
+            Some(message) => Some(message),
+            None => self.payload.downcast_ref::<String>().map(String::as_str),
+        }
+    }
+}
+
+/// Like [`run_simulations_on_with_progress`], but stops at the first game that panics and returns
+/// its seed instead of unwinding out of the whole sweep with no hint of which game it was.
+///
+/// Catching panics costs a little on every game, so this is meant for tracking down a strategy
+/// bug rather than for every run.
+pub fn run_simulations_on_catching<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> Result<SimulationSummary, GamePanic> {
+    let start = Instant::now();
+    let mut game = game.clone();
+    let mut totals = Totals::with_gravy_threshold(game.gravy_threshold());
+    let mut unreported = 0;
+
+    for i in seeds {
+        let played = panic::catch_unwind(AssertUnwindSafe(|| {
+            play(strategy, &mut game, &mut SmallRng::seed_from_u64(i))
+        }));
+        match played {
+            Ok(result) => totals.add(i, result),
+            Err(payload) => {
+                report_progress(Some(progress), &mut unreported);
+                return Err(GamePanic { seed: i, payload });
+            }
+        }
+
+        unreported += 1;
+        if unreported == PROGRESS_INTERVAL {
+            report_progress(Some(progress), &mut unreported);
+        }
+    }
+    report_progress(Some(progress), &mut unreported);
+
+    Ok(totals.finish(start.elapsed()))
+}
+
+/// Like [`run_simulations_on_with_progress`], but also returns how long `strategy` spent choosing
+/// dice, out of the whole run's [`duration`](SimulationSummary::duration).
+///
+/// Timing every decision adds a little to every round, so this is meant for profiling a strategy
+/// rather than for every run. Scores are the same as without the timing.
+pub fn run_simulations_on_profiled<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> (SimulationSummary, Duration) {
+    let mut timed = timed(strategy);
+    let summary = run_simulations_on_with_progress(&mut timed, game, seeds, progress);
+    (summary, timed.elapsed)
+}
+
+/// Recommends how many games to play on `game` for the `confidence` interval around `strategy`'s
+/// average to reach `margin` points either side, by playing `pilot` games first to estimate how
+/// spread out its scores are. See [`sample_size_for`].
+///
+/// The pilot plays seeds from 0, so the full run can start at `pilot` to avoid reusing them.
+pub fn recommend_simulations<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    pilot: u64,
+    margin: f64,
+    confidence: f64,
+) -> u64 {
+    let summary = run_simulations_on(strategy, game, 0..pilot);
+    sample_size_for(margin, confidence, summary.std_dev)
+}
+
+/// Counts how many games ended with each final score.
+pub fn score_histogram<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    num_simulations: u64,
+) -> BTreeMap<u16, u64> {
+    score_histogram_range(strategy, 0..num_simulations)
+}
+
+/// Like [`score_histogram`], but plays an arbitrary range of seeds.
+pub fn score_histogram_range<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    seeds: Range<u64>,
+) -> BTreeMap<u16, u64> {
+    score_histogram_on(strategy, &Game::new(), seeds)
+}
+
+/// Like [`score_histogram_range`], but every game starts from the dice `game` started with and
+/// follows its rules.
+pub fn score_histogram_on<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+) -> BTreeMap<u16, u64> {
+    tally(
+        strategy,
+        &mut game.clone(),
+        seeds,
+        SmallRng::seed_from_u64,
+        None,
+    )
+    .histogram()
+}
+
+/// Writes the final score of every game in `seeds` to `out`, in seed order, as a little-endian
+/// `u16` per game.
+///
+/// Scores are written as they're played, so `out` should be buffered but nothing else is kept in
+/// memory. The result can be read with e.g. `numpy.fromfile(path, dtype="<u2")`.
+pub fn dump_scores<S, W>(strategy: &mut S, seeds: Range<u64>, out: W) -> io::Result<()>
+where
+    S: Strategy + ?Sized,
+    W: Write,
+{
+    dump_scores_on(strategy, &Game::new(), seeds, out)
+}
+
+/// Like [`dump_scores`], but every game starts from the dice `game` started with and follows its
+/// rules.
+pub fn dump_scores_on<S, W>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+    mut out: W,
+) -> io::Result<()>
+where
+    S: Strategy + ?Sized,
+    W: Write,
+{
+    let mut game = game.clone();
+
+    for i in seeds {
+        let result = play(strategy, &mut game, &mut SmallRng::seed_from_u64(i));
+        out.write_all(&result.points.to_le_bytes())?;
+    }
+
+    out.flush()
+}
+
+/// Lists every seed in `0..num_simulations` where the strategy finished with zero points.
+pub fn gravy_seeds<S: Strategy + ?Sized>(strategy: &mut S, num_simulations: u64) -> Vec<u64> {
+    let mut seeds = Vec::new();
+
+    run_simulations_with(strategy, 0..num_simulations, |i, points| {
+        if GameOutcome::from_points(points) == GameOutcome::Gravy {
+            seeds.push(i);
+        }
+    });
+
+    seeds
+}
+
+/// Plays `batches` batches of `games_per_batch` games each, on back to back runs of seeds starting
+/// from zero, and returns the mean and sample variance of the batches' gravy rates.
+///
+/// The variance says how far a gravy rate measured over one batch this size strays, so the
+/// standard error of the mean is `(variance / batches as f64).sqrt()`. A difference between two
+/// strategies' means that is small next to their standard errors may just be noise. A single
+/// batch has no spread to measure, so its variance is zero.
+///
+/// # Panics
+///
+/// Panics if `batches` or `games_per_batch` is zero.
+pub fn batched_gravy_stats<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    batches: u64,
+    games_per_batch: u64,
+) -> (f64, f64) {
+    assert!(batches > 0, "need at least one batch");
+    assert!(games_per_batch > 0, "need at least one game per batch");
+
+    let mut rates = Moments::new();
+    for batch in 0..batches {
+        let start = batch * games_per_batch;
+        let mut gravies = 0;
+        run_simulations_with(strategy, start..start + games_per_batch, |_, points| {
+            if GameOutcome::from_points(points) == GameOutcome::Gravy {
+                gravies += 1;
+            }
+        });
+        rates.add(gravies as f64 / games_per_batch as f64);
+    }
+
+    let n = batches as f64;
+    let sample_variance = if batches > 1 {
+        rates.variance() * n / (n - 1.0)
+    } else {
+        0.0
+    };
+    (rates.mean(), sample_variance)
+}
+
+/// Parallel version of [`run_simulations`], producing identical results for the same range.
+///
+/// Every rayon job plays its games with its own clone of `strategy`, so state a strategy carries
+/// from one game to the next isn't shared between jobs.
+#[cfg(feature = "rayon")]
+pub fn run_simulations_par<S: Strategy + Clone + Send + Sync>(
+    strategy: &S,
+    num_simulations: u64,
+) -> SimulationSummary {
+    run_simulations_par_range(strategy, 0..num_simulations)
+}
+
+/// Like [`run_simulations_par`], but plays an arbitrary range of seeds.
+#[cfg(feature = "rayon")]
+pub fn run_simulations_par_range<S: Strategy + Clone + Send + Sync>(
+    strategy: &S,
+    seeds: Range<u64>,
+) -> SimulationSummary {
+    run_simulations_par_on(strategy, &Game::new(), seeds)
+}
+
+/// Parallel version of [`run_simulations_on`].
+#[cfg(feature = "rayon")]
+pub fn run_simulations_par_on<S: Strategy + Clone + Send + Sync>(
+    strategy: &S,
+    game: &Game,
+    seeds: Range<u64>,
+) -> SimulationSummary {
+    run_simulations_par_on_with_progress(strategy, game, seeds, &AtomicU64::new(0))
+}
+
+/// Parallel version of [`run_simulations_on_with_progress`].
+#[cfg(feature = "rayon")]
+pub fn run_simulations_par_on_with_progress<S: Strategy + Clone + Send + Sync>(
+    strategy: &S,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> SimulationSummary {
+    use rayon::prelude::*;
+
+    let start = Instant::now();
+    let totals = || Totals::with_gravy_threshold(game.gravy_threshold());
+    seeds
+        .into_par_iter()
+        .fold(
+            || (strategy.clone(), game.clone(), totals(), 0),
+            |(mut strategy, mut game, mut totals, mut unreported), i| {
+                let mut rng = SmallRng::seed_from_u64(i);
+                totals.add(i, play(&mut strategy, &mut game, &mut rng));
+
+                unreported += 1;
+                if unreported == PROGRESS_INTERVAL {
+                    report_progress(Some(progress), &mut unreported);
+                }
+                (strategy, game, totals, unreported)
+            },
+        )
+        .map(|(_, _, totals, mut unreported)| {
+            report_progress(Some(progress), &mut unreported);
+            totals
+        })
+        .reduce(totals, Totals::merge)
+        .finish(start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_game_simulation() {
+        let points = simulate_game(&mut FnStrategy(all_zero_or_big_min_strategy), 800);
+        assert_eq!(0, points);
+    }
+
+    // Scores every built-in strategy got on a few seeds, gravies included, so a refactor that
+    // changes any of their choices shows up here
+    const GOLDEN_SCORES: [(&str, u64, u16); 20] = [
+        ("All Zero/Big Min", 735, 0),
+        ("All Zero/Big Min", 42, 9),
+        ("All Big Zero/One Zero/Big Min", 420, 0),
+        ("All Big Zero/One Zero/Big Min", 7, 12),
+        ("All Zero/Prio Min", 735, 0),
+        ("All Zero/Prio Min", 42, 9),
+        ("All Zero + Big Min", 800, 12),
+        ("All Zero + Big Min", 42, 21),
+        ("All Zero/Best Gain", 735, 0),
+        ("All Zero/Best Gain", 42, 9),
+        ("Fettermania Blackjack", 735, 0),
+        ("Fettermania Blackjack", 800, 1),
+        ("Fish for Big Zeros", 420, 0),
+        ("Fish for Big Zeros", 42, 7),
+        ("Lookahead", 800, 0),
+        ("Lookahead", 7, 2),
+        ("One Max", 800, 70),
+        ("One Max", 42, 72),
+        ("Random", 800, 27),
+        ("Random", 7, 47),
+    ];
+
+    #[test]
+    fn test_golden_scores() {
+        for (name, seed, expected) in GOLDEN_SCORES {
+            // A fresh strategy every time, so stateful ones don't depend on the rows before
+            let (_, mut strategy) = all_strategies()
+                .into_iter()
+                .find(|(n, _)| *n == name)
+                .unwrap();
+            assert_eq!(
+                expected,
+                simulate_game(&mut strategy, seed),
+                "{} on seed {}",
+                name,
+                seed
+            );
+        }
+
+        for (name, _) in all_strategies() {
+            assert!(
+                GOLDEN_SCORES.iter().any(|(n, _, _)| *n == name),
+                "no golden scores for {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_simulate_game_capped() {
+        struct CountingStrategy {
+            rounds: usize,
+        }
+
+        impl Strategy for CountingStrategy {
+            fn select(&mut self, dice: &[Die]) -> Vec<usize> {
+                self.rounds += 1;
+                fettermania_blackjack_strategy(dice)
+            }
+        }
+
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        for seed in 0..100 {
+            let points = simulate_game(&mut strategy, seed);
+            let trace = simulate_game_traced(&mut strategy, seed);
+
+            assert_eq!(
+                Some(points),
+                simulate_game_capped(&mut strategy, seed, u16::MAX)
+            );
+            assert_eq!(
+                Some(points),
+                simulate_game_capped(&mut strategy, seed, points)
+            );
+            if points > 0 {
+                assert_eq!(None, simulate_game_capped(&mut strategy, seed, points - 1));
+            }
+
+            // With no points allowed, the game stops in the first round that scores any
+            let mut counting = CountingStrategy { rounds: 0 };
+            let capped = simulate_game_capped(&mut counting, seed, 0);
+            match trace.rounds.iter().position(|round| round.points > 0) {
+                Some(first) => {
+                    assert_eq!(None, capped);
+                    assert_eq!(first + 1, counting.rounds);
+                }
+                None => assert_eq!(Some(0), capped),
+            }
+        }
+    }
+
+    #[test]
+    fn test_scores_past_u8() {
+        // Forty d20s can't all be taken off without scoring well over 255
+        let game = Game::with_composition(&[(Faces::TWENTY, 40)]);
+        let mut strategy = FnStrategy(one_max_strategy);
+        let summary = run_simulations_on(&mut strategy, &game, 0..100);
+
+        assert!(summary.min_points > u8::MAX as u16);
+        assert!(summary.max_points <= 40 * 19);
+
+        let mut game = game.clone();
+        let result = play(&mut strategy, &mut game, &mut SmallRng::seed_from_u64(7));
+        assert_eq!(game.score(), result.points);
+        assert_eq!(40, result.rounds);
+    }
+
+    #[test]
+    fn test_all_strategies() {
+        let strategies = all_strategies();
+        let mut names: Vec<&str> = strategies.iter().map(|(name, _)| *name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(strategies.len(), names.len());
+
+        for (_, mut strategy) in strategies {
+            let summary = run_simulations(&mut strategy, 100);
+            assert!(summary.max_points <= Game::new().board_potential() as u16);
+
+            // Every size of die on its own, so no strategy assumes the default dice
+            for faces in Faces::all() {
+                let game = Game::with_composition(&[(faces, 3)]);
+                let summary = run_simulations_on(&mut strategy, &game, 0..20);
+                assert!(summary.max_points <= game.board_potential() as u16);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_strategies_terminate() {
+        // Built-in strategies never pass, so every round takes at least one die off the board
+        let max_rounds = Game::new().current_dice().len();
+        let max_points = Game::new().board_potential() as u16;
+
+        for (name, mut strategy) in all_strategies() {
+            for seed in 0..10_000 {
+                let mut rng = SmallRng::seed_from_u64(seed);
+                let mut game = Game::new();
+                let mut rounds = 0;
+
+                while !game.is_over() {
+                    rounds += 1;
+                    assert!(
+                        rounds <= max_rounds,
+                        "{} took more than {} rounds on seed {}",
+                        name,
+                        max_rounds,
+                        seed
+                    );
+
+                    game.roll_all(&mut rng);
+                    let dice = game.current_dice().len();
+                    let mut indices = strategy.select(game.current_dice());
+                    assert!(
+                        !indices.is_empty() && indices.iter().all(|&i| i < dice),
+                        "{} picked {:?} out of {} dice on seed {}",
+                        name,
+                        indices,
+                        dice,
+                        seed
+                    );
+                    game.remove_dice(&mut indices);
+                }
+
+                assert!(
+                    game.score() <= max_points,
+                    "{} scored {} on seed {}",
+                    name,
+                    game.score(),
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_simulate_game_limited() {
+        // Removing one die at a time takes 15 rounds, so 5 leaves 10 dice to score as they lie
+        let mut strategy = FnStrategy(one_max_strategy);
+        let seed = 3;
+        let trace = simulate_game_traced(&mut strategy, seed);
+
+        let last = &trace.rounds[4];
+        let left: u16 = (0..last.dice.len())
+            .filter(|i| !last.removed.contains(i))
+            .map(|i| last.dice[i].points() as u16)
+            .sum();
+        let removed: u16 = trace.rounds[..5].iter().map(|round| round.points).sum();
+
+        let limited = simulate_game_limited(&mut strategy, seed, 5);
+        assert_eq!(removed + left, limited);
+        assert_ne!(simulate_game(&mut strategy, seed), limited);
+
+        // A limit the game never reaches changes nothing
+        for seed in 0..100 {
+            assert_eq!(
+                simulate_game(&mut strategy, seed),
+                simulate_game_limited(&mut strategy, seed, 15)
+            );
+        }
+
+        // No rounds at all still rolls the board once, and all of it scores
+        let mut game = Game::new();
+        game.roll_all(&mut SmallRng::seed_from_u64(seed));
+        assert_eq!(
+            game.board_points() as u16,
+            simulate_game_limited(&mut strategy, seed, 0)
+        );
+
+        // A single d4 only ever gets its one roll
+        let d4 = Game::with_composition(&[(Faces::FOUR, 1)]);
+        for seed in 0..50 {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut game = d4.clone();
+            game.roll_all(&mut rng);
+            let first = game.board_points() as u16;
+            assert_eq!(first, simulate_game_limited_on(&mut strategy, &d4, seed, 0));
+            assert_eq!(first, simulate_game_limited_on(&mut strategy, &d4, seed, 1));
+        }
+    }
+
+    #[test]
+    fn test_keep_one() {
+        let dice = vec![
+            Die::six().with_points(0),
+            Die::eight().with_points(3),
+            Die::twelve().with_points(5),
+        ];
+
+        // The die showing the most points stays on the board
+        let mut indices = vec![0, 1, 2];
+        keep_one(&dice, &mut indices);
+        assert_eq!(vec![0, 1], indices);
+
+        // Anything short of clearing the board is left alone
+        let mut indices = vec![1, 2];
+        keep_one(&dice, &mut indices);
+        assert_eq!(vec![1, 2], indices);
+
+        // So is clearing it with nothing but zeros
+        let zeros = vec![Die::six().with_points(0), Die::ten().with_points(0)];
+        let mut indices = vec![0, 1];
+        keep_one(&zeros, &mut indices);
+        assert_eq!(vec![0, 1], indices);
+
+        // The last die on its own only comes off as a zero
+        let mut indices = vec![0];
+        keep_one(&dice[2..], &mut indices);
+        assert!(indices.is_empty());
+    }
+
+    #[test]
+    fn test_simulate_game_keep_one() {
+        // Only ever taking zeros never runs into the rule
+        let mut strategy = FnStrategy(only_zero_strategy);
+        for seed in 0..20 {
+            assert_eq!(0, simulate_game_keep_one(&mut strategy, seed));
+        }
+
+        // One Max takes one die a round, so the rule only kicks in on the last die, which gets
+        // rerolled until it comes off for free
+        let mut strategy = FnStrategy(one_max_strategy);
+        for seed in 0..100 {
+            let trace = simulate_game_traced(&mut strategy, seed);
+            let last = trace.rounds.last().unwrap().points;
+
+            assert_eq!(
+                simulate_game(&mut strategy, seed) - last,
+                simulate_game_keep_one(&mut strategy, seed)
+            );
+        }
+
+        // A configured game keeps its own dice and rules
+        let game =
+            Game::with_composition(&[(Faces::FOUR, 2)]).with_roll_model(RollModel::Inclusive);
+        for seed in 0..50 {
+            let trace = simulate_game_traced_on(&mut strategy, &game, seed);
+            let last = trace.rounds.last().unwrap().points;
+
+            assert_eq!(
+                trace.total_points() - last,
+                simulate_game_keep_one_on(&mut strategy, &game, seed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rounds() {
+        // Removing one die at a time always takes a round per die
+        let mut strategy = FnStrategy(one_max_strategy);
+        for seed in 0..10 {
+            assert_eq!(15, simulate_game_result(&mut strategy, seed).rounds);
+        }
+        assert_eq!(15.0, run_simulations(&mut strategy, 10).avg_rounds);
+
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let result = simulate_game_result(&mut strategy, 3);
+        assert_eq!(simulate_game(&mut strategy, 3), result.points);
+        assert_eq!(
+            simulate_game_traced(&mut strategy, 3).rounds.len() as u32,
+            result.rounds
+        );
+    }
+
+    #[test]
+    fn test_custom_rng() {
+        // xorshift64*, just to have a generator that isn't SmallRng
+        struct XorShift(u64);
+
+        impl rand::RngCore for XorShift {
+            fn next_u32(&mut self) -> u32 {
+                (self.next_u64() >> 32) as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.0 ^= self.0 >> 12;
+                self.0 ^= self.0 << 25;
+                self.0 ^= self.0 >> 27;
+                self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+            }
+
+            fn fill_bytes(&mut self, dst: &mut [u8]) {
+                rand::rand_core::impls::fill_bytes_via_next(self, dst)
+            }
+        }
+
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+
+        let small = run_simulations_with_rng(&mut strategy, 0..1000, SmallRng::seed_from_u64);
+        let xorshift = run_simulations_with_rng(&mut strategy, 0..1000, |seed| XorShift(seed + 1));
+
+        assert_eq!(
+            run_simulations(&mut strategy, 1000).avg_points,
+            small.avg_points
+        );
+        assert!((small.avg_points - xorshift.avg_points).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_only_zero_strategy() {
+        // Waiting for zeros is free as long as they keep coming up
+        for seed in 0..100 {
+            assert_eq!(0, simulate_game(&mut FnStrategy(only_zero_strategy), seed));
+        }
+
+        let summary = run_simulations_range(&mut FnStrategy(only_zero_strategy), 40..90);
+        assert_eq!(50, summary.longest_gravy_streak);
+    }
+
+    #[test]
+    fn test_pass_cap() {
+        let trace = simulate_game_traced(&mut FnStrategy(|_| Vec::new()), 800);
+
+        // Every die is forced out after a full run of passes
+        assert_eq!(
+            15 * (MAX_CONSECUTIVE_PASSES as usize + 1),
+            trace.rounds.len()
+        );
+        for rounds in trace.rounds.chunks(MAX_CONSECUTIVE_PASSES as usize + 1) {
+            let (forced, passed) = rounds.split_last().unwrap();
+            assert!(passed.iter().all(|round| round.removed.is_empty()));
+            assert_eq!(1, forced.removed.len());
+        }
+    }
+
+    #[test]
+    fn test_run_simulations_median() {
+        let summary = run_simulations(&mut FnStrategy(all_zero_or_big_min_strategy), 11);
+
+        // Seeds 0..11 score 3 8 6 4 8 12 6 2 9 4 12, or sorted 2 3 4 4 6 [6] 8 8 9 12 12
+        assert_eq!(6, summary.median);
+        assert_eq!(2, summary.min_points);
+        assert_eq!(12, summary.max_points);
+    }
+
+    #[test]
+    fn test_extreme_seeds() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let summary = run_simulations_range(&mut strategy, 3..11);
+
+        // Seeds 3..11 score 4 8 12 6 2 9 4 12, so the worst seed is the first 12
+        assert_eq!(5, summary.worst_seed);
+        assert_eq!(7, summary.best_seed);
+        assert_eq!(
+            summary.max_points,
+            simulate_game(&mut strategy, summary.worst_seed)
+        );
+        assert_eq!(
+            summary.min_points,
+            simulate_game(&mut strategy, summary.best_seed)
+        );
+    }
+
+    #[test]
+    fn test_dump_scores() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+        let path = std::env::temp_dir().join(format!("biscuits-scores-{}", std::process::id()));
+
+        let file = std::fs::File::create(&path).unwrap();
+        dump_scores(&mut strategy, 100..300, io::BufWriter::new(file)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(400, bytes.len());
+        for (seed, points) in (100..).zip(bytes.chunks(2)) {
+            let points = u16::from_le_bytes([points[0], points[1]]);
+            assert_eq!(simulate_game(&mut strategy, seed), points);
+        }
+
+        let game = Game::with_composition(&[(Faces::SIX, 2)]).with_scoring(ScoringMode::FaceValue);
+        let mut bytes = Vec::new();
+        dump_scores_on(&mut strategy, &game, 0..50, &mut bytes).unwrap();
+        // Every die costs its six faces however it's removed
+        assert!(bytes.chunks(2).all(|points| points == 12u16.to_le_bytes()));
+    }
+
+    #[test]
+    fn test_gravy_seeds() {
+        let mut strategy = FnStrategy(all_zero_or_prio_min_strategy);
+
+        let seeds = gravy_seeds(&mut strategy, 2000);
+        let summary = run_simulations(&mut strategy, 2000);
+
+        assert_eq!(summary.gravies, seeds.len() as u64);
+        for seed in seeds {
+            assert_eq!(0, simulate_game(&mut strategy, seed));
+        }
+    }
+
+    #[test]
+    fn test_no_simulations() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+
+        let summary = run_simulations(&mut strategy, 0);
+        assert_eq!((0, 0.0), (summary.games, summary.avg_points));
+        assert!(!summary.std_dev.is_nan());
+        assert_eq!(0, run_simulations_fast(&mut strategy, 0, 1).games);
+        assert_eq!(0, run_simulations_range(&mut strategy, 5..5).games);
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(0, run_simulations_par(&strategy, 0).games);
+    }
+
+    #[test]
+    fn test_batched_gravy_stats() {
+        let mut strategy = FnStrategy(all_zero_or_prio_min_strategy);
+
+        let (mean, variance) = batched_gravy_stats(&mut strategy, 40, 100);
+        let summary = run_simulations(&mut strategy, 4000);
+        assert!((summary.gravies as f64 / 4000.0 - mean).abs() < 1e-12);
+        assert!(variance > 0.0);
+
+        // Each batch's rate spreads about the same however many there are, so sixteen times the
+        // batches should cut the standard error of the mean to about a quarter
+        let (_, few) = batched_gravy_stats(&mut strategy, 10, 100);
+        let (_, many) = batched_gravy_stats(&mut strategy, 160, 100);
+        let ratio = (many / 160.0).sqrt() / (few / 10.0).sqrt();
+        assert!((0.15..0.4).contains(&ratio), "ratio was {ratio}");
+
+        let (single, variance) = batched_gravy_stats(&mut strategy, 1, 4000);
+        assert!((single - mean).abs() < 1e-12);
+        assert_eq!(0.0, variance);
+    }
+
+    #[cfg(feature = "stable-rng")]
+    #[test]
+    fn test_stable_rng() {
+        // Golden values: if these change, every stable result published so far has too
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let scores: Vec<u16> = (0..10)
+            .map(|seed| simulate_game_stable(&mut strategy, seed))
+            .collect();
+        assert_eq!(vec![9, 7, 4, 11, 5, 12, 9, 10, 7, 12], scores);
+
+        let summary = run_simulations_stable(&mut strategy, 0..1000);
+        assert_eq!(8889, (summary.avg_points * 1000.0).round() as u64);
+        assert_eq!(3, summary.gravies);
+    }
+
+    #[test]
+    fn test_simulate_game_outcome() {
+        let mut strategy = FnStrategy(all_zero_or_prio_min_strategy);
+
+        let mut gravies = 0;
+        for seed in 0..2000 {
+            match simulate_game_outcome(&mut strategy, seed) {
+                GameOutcome::Gravy => gravies += 1,
+                GameOutcome::Scored(points) => {
+                    assert_ne!(0, points);
+                    assert_eq!(simulate_game(&mut strategy, seed), points);
+                }
+            }
+        }
+        assert_eq!(run_simulations(&mut strategy, 2000).gravies, gravies);
+
+        assert_eq!(GameOutcome::Gravy, GameOutcome::from_points(0));
+        assert_eq!(7, GameOutcome::from_points(7).points());
+    }
+
+    #[test]
+    fn test_confidence_interval() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        let widths: Vec<f64> = [100, 1000, 10000]
+            .into_iter()
+            .map(|n| {
+                let summary = run_simulations(&mut strategy, n);
+                let (low, high) = summary.confidence_interval();
+                assert!(low < summary.avg_points && summary.avg_points < high);
+                high - low
+            })
+            .collect();
+
+        assert!(widths[0] > widths[1] && widths[1] > widths[2]);
+    }
+
+    #[test]
+    fn test_stateful_strategy() {
+        struct CountingStrategy {
+            removed: usize,
+        }
+
+        impl Strategy for CountingStrategy {
+            fn select(&mut self, dice: &[Die]) -> Vec<usize> {
+                let indices = all_zero_or_big_min_strategy(dice);
+                self.removed += indices.len();
+                indices
+            }
+        }
+
+        let mut strategy = CountingStrategy { removed: 0 };
+        let points = simulate_game(&mut strategy, 800);
+
+        assert_eq!(0, points);
+        assert_eq!(15, strategy.removed);
+    }
+
+    #[test]
+    fn test_on_new_game() {
+        // Takes one die a round and counts rounds, so a count that carried over between games
+        // would go past the board
+        #[derive(Default)]
+        struct RoundCounter {
+            rounds: usize,
+            games: usize,
+            most_rounds: usize,
+        }
+
+        impl Strategy for RoundCounter {
+            fn select(&mut self, dice: &[Die]) -> Vec<usize> {
+                self.rounds += 1;
+                self.most_rounds = self.most_rounds.max(self.rounds);
+                one_max_strategy(dice)
+            }
+
+            fn on_new_game(&mut self) {
+                self.rounds = 0;
+                self.games += 1;
+            }
+        }
+
+        let mut strategy = RoundCounter::default();
+        run_simulations(&mut strategy, 50);
+        assert_eq!(50, strategy.games);
+        assert_eq!(15, strategy.most_rounds);
+
+        simulate_game_traced(&mut strategy, 3);
+        simulate_game_limited(&mut strategy, 3, 5);
+        simulate_game_keep_one(&mut strategy, 3);
+        assert_eq!(53, strategy.games);
+
+        // Forwarded through the wrappers
+        let mut wrapped = timed(canonical(clear_zeros_then(RoundCounter::default())));
+        run_simulations(&mut wrapped, 10);
+        assert_eq!(10, wrapped.inner.0.0.games);
+    }
+
+    #[test]
+    fn test_run_simulations_range() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+
+        let summary = run_simulations_range(&mut strategy, 5..11);
+
+        // Seeds 5..11 score 12 6 2 9 4 12
+        assert_eq!(7.5, summary.avg_points);
+        assert_eq!(2, summary.min_points);
+        assert_eq!(12, summary.max_points);
+    }
+
+    #[test]
+    fn test_run_simulations_with() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let mut played = Vec::new();
+
+        run_simulations_with(&mut strategy, 0..500, |i, points| played.push((i, points)));
+
+        assert_eq!(500, played.len());
+        for (expected, &(i, points)) in played.iter().enumerate() {
+            assert_eq!(expected as u64, i);
+            assert_eq!(simulate_game(&mut strategy, i), points);
+        }
+
+        let summary = run_simulations(&mut strategy, 500);
+        let total: u64 = played.iter().map(|&(_, points)| points as u64).sum();
+        assert_eq!(summary.avg_points, total as f64 / 500.0);
+
+        run_simulations_with(&mut strategy, 3..3, |_, _| panic!("no seeds to play"));
+    }
+
+    #[test]
+    fn test_run_simulations_on_catching() {
+        let game = Game::new();
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let caught = run_simulations_on_catching(&mut strategy, &game, 0..500, &AtomicU64::new(0));
+        let summary = run_simulations_on(&mut strategy, &game, 0..500);
+        assert_eq!(summary.avg_points, caught.unwrap().avg_points);
+
+        // Panics the first time a game rolls a zero on the d12
+        let mut strategy = FnStrategy(|dice: &[Die]| {
+            let d12 = dice.iter().find(|die| die.faces() == Faces::TWELVE);
+            assert!(d12.is_none_or(|die| die.points() != 0), "zero on the d12");
+            all_zero_or_big_min_strategy(dice)
+        });
+        let expected = (0..500)
+            .find(|&seed| {
+                panic::catch_unwind(AssertUnwindSafe(|| simulate_game(&mut strategy, seed)))
+                    .is_err()
+            })
+            .unwrap();
+
+        let progress = AtomicU64::new(0);
+        let panicked = run_simulations_on_catching(&mut strategy, &game, 0..500, &progress);
+        let panicked = panicked.unwrap_err();
+        assert_eq!(expected, panicked.seed);
+        assert_eq!(panicked.seed, progress.load(Ordering::Relaxed));
+        assert_eq!(Some("zero on the d12"), panicked.message());
+    }
+
+    #[test]
+    fn test_run_simulations_fast() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let fast = run_simulations_fast(&mut strategy, 20000, 0);
+        let seeded = run_simulations(&mut strategy, 20000);
+
+        assert_eq!(20000, fast.games);
+        let again = run_simulations_fast(&mut strategy, 20000, 0);
+        assert_eq!(
+            (fast.avg_points, fast.worst_seed),
+            (again.avg_points, again.worst_seed)
+        );
+        assert_ne!(
+            fast.avg_points,
+            run_simulations_fast(&mut strategy, 20000, 1).avg_points
+        );
+
+        // Different games, but well inside four standard errors of each other
+        let error = (fast.std_error.powi(2) + seeded.std_error.powi(2)).sqrt();
+        assert!((fast.avg_points - seeded.avg_points).abs() < 4.0 * error);
+
+        let rate = seeded.gravies as f64 / 20000.0;
+        let error = (2.0 * rate * (1.0 - rate) / 20000.0).sqrt();
+        let fast_rate = fast.gravies as f64 / 20000.0;
+        assert!(
+            (fast_rate - rate).abs() < 4.0 * error,
+            "{} gravies against {}",
+            fast.gravies,
+            seeded.gravies
+        );
+    }
+
+    #[test]
+    fn test_run_simulations_on_profiled() {
+        let game = Game::new();
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        let (profiled, deciding) =
+            run_simulations_on_profiled(&mut strategy, &game, 0..1000, &AtomicU64::new(0));
+        let summary = run_simulations_on(&mut strategy, &game, 0..1000);
+
+        assert_eq!(summary.avg_points, profiled.avg_points);
+        assert_eq!(summary.worst_seed, profiled.worst_seed);
+        assert_eq!(summary.gravies, profiled.gravies);
+        assert_eq!(summary.avg_rounds, profiled.avg_rounds);
+        assert!(deciding > Duration::ZERO);
+        assert!(deciding < profiled.duration);
+    }
+
+    #[test]
+    fn test_score_histogram() {
+        let mut strategy = FnStrategy(all_zero_or_prio_min_strategy);
+
+        let histogram = score_histogram(&mut strategy, 2000);
+        let summary = run_simulations(&mut strategy, 2000);
+
+        assert_eq!(2000, histogram.values().sum::<u64>());
+        assert_eq!(Some(&summary.gravies), histogram.get(&0));
+
+        // A single d4 scores whatever it rolls
+        let game = Game::with_composition(&[(Faces::FOUR, 1)]);
+        let histogram = score_histogram_on(&mut strategy, &game, 0..2000);
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            histogram.keys().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            score_histogram(&mut strategy, 300),
+            score_histogram_on(&mut strategy, &Game::new(), 0..300)
+        );
+    }
+
+    #[test]
+    fn test_run_simulations_on() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+
+        let default = run_simulations_on(&mut strategy, &Game::new(), 0..11);
+        assert_eq!(
+            run_simulations(&mut strategy, 11).avg_points,
+            default.avg_points
+        );
+
+        // A single d4 scores whatever its one roll is, a quarter of the time zero
+        let game = Game::with_composition(&[(Faces::FOUR, 1)]);
+        let summary = run_simulations_on(&mut strategy, &game, 0..1000);
+        assert_eq!(3, summary.max_points);
+        assert!((summary.avg_points - 1.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_gravy_threshold() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let strict =
+            run_simulations_on(&mut strategy, &Game::new().with_gravy_threshold(0), 0..2000);
+        let default = run_simulations(&mut strategy, 2000);
+        assert_eq!(default.gravies, strict.gravies);
+        assert_eq!(default.longest_gravy_streak, strict.longest_gravy_streak);
+
+        let histogram = score_histogram(&mut strategy, 2000);
+        let mut previous = strict;
+        for threshold in 1..=5 {
+            let game = Game::new().with_gravy_threshold(threshold);
+            let summary = run_simulations_on(&mut strategy, &game, 0..2000);
+
+            let expected: u64 = histogram.range(..=threshold).map(|(_, count)| count).sum();
+            assert_eq!(expected, summary.gravies);
+            assert!(summary.gravies > previous.gravies);
+            assert!(summary.longest_gravy_streak >= previous.longest_gravy_streak);
+            // Only the gravies change
+            assert_eq!(default.avg_points, summary.avg_points);
+            previous = summary;
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            let game = Game::new().with_gravy_threshold(5);
+            let par = run_simulations_par_on(&strategy, &game, 0..2000);
+            assert_eq!(previous.gravies, par.gravies);
+            assert_eq!(previous.longest_gravy_streak, par.longest_gravy_streak);
+        }
+    }
+
+    #[test]
+    fn test_recommend_simulations() {
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let game = Game::new();
+        let pilot = run_simulations_on(&mut strategy, &game, 0..1000);
+
+        let recommended = recommend_simulations(&mut strategy, &game, 1000, 0.05, 0.95);
+        assert_eq!(sample_size_for(0.05, 0.95, pilot.std_dev), recommended);
+        // A standard deviation around 4 needs tens of thousands of games
+        assert!((15000..40000).contains(&recommended));
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+        let progress = AtomicU64::new(0);
+
+        let summary =
+            run_simulations_on_with_progress(&mut strategy, &Game::new(), 0..3000, &progress);
+        assert_eq!(3000, progress.load(Ordering::Relaxed));
+        assert_eq!(
+            run_simulations(&mut strategy, 3000).avg_points,
+            summary.avg_points
+        );
+
+        #[cfg(feature = "rayon")]
+        {
+            let progress = AtomicU64::new(0);
+            run_simulations_par_on_with_progress(&strategy, &Game::new(), 0..3000, &progress);
+            assert_eq!(3000, progress.load(Ordering::Relaxed));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_run_simulations_par() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        let sequential = run_simulations(&mut strategy, 1000);
+        let parallel = run_simulations_par(&strategy, 1000);
+
+        assert_eq!(
+            sequential,
+            SimulationSummary {
+                duration: sequential.duration,
+                ..parallel
+            }
+        );
+    }
+}
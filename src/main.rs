@@ -1,504 +1,1129 @@
-use rand::Rng;
-use rand::SeedableRng;
-use rand::rngs::SmallRng;
-use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::time::Instant;
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum Faces {
-    Six,
-    Eight,
-    Ten,
-    Twelve,
+use biscuits::{
+    BoxedStrategy, Faces, Game, MAX_ORACLE_DICE, RollModel, SimulationSummary, all_strategies,
+    score_value,
+};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal};
+use std::ops::Range;
+use std::panic;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Table,
+    Csv,
+    #[cfg(feature = "serde")]
+    Json,
 }
 
-impl Faces {
-    fn value(&self) -> u8 {
-        match self {
-            Faces::Six => 6,
-            Faces::Eight => 8,
-            Faces::Ten => 10,
-            Faces::Twelve => 12,
-        }
-    }
+// What to order the results by. Gravies go from most to fewest, everything else from lowest to
+// highest. Value is the average with a bonus for gravies, see `biscuits::score_value`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortKey {
+    Avg,
+    Gravies,
+    Max,
+    Time,
+    Value,
 }
 
-#[derive(Debug, Clone)]
-struct Die {
-    faces: Faces,
-    points: u8,
+#[cfg(not(feature = "serde"))]
+const FORMATS: &str = "table, csv";
+#[cfg(feature = "serde")]
+const FORMATS: &str = "table, csv, json";
+
+struct Args {
+    format: Format,
+    sort: SortKey,
+    gravy_weight: f64,
+    gravy_threshold: u16,
+    roll_model: RollModel,
+    simulations: u64,
+    seed_offset: u64,
+    // None runs every strategy
+    strategy: Option<String>,
+    histogram: Option<String>,
+    replay: Option<u64>,
+    dump_scores: Option<String>,
+    regret: bool,
+    win_matrix: bool,
+    progress: bool,
+    debug: bool,
+    profile: bool,
+    // None plays the default number of d6s
+    sixes: Option<usize>,
+    #[cfg(feature = "serde")]
+    config: Option<String>,
+    verbose: bool,
+    precision: usize,
 }
 
-impl Die {
-    fn six() -> Self {
-        Die {
-            faces: Faces::Six,
-            points: 6,
+impl Args {
+    // Flags that can also come from the environment fall back to `env(name)` when they aren't
+    // passed. Flags always win over the environment, which wins over the defaults.
+    fn parse(
+        mut args: impl Iterator<Item = String>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let mut simulations_passed = false;
+        let mut strategy_passed = false;
+        let mut parsed = Args {
+            format: Format::Table,
+            sort: SortKey::Avg,
+            gravy_weight: 1.0,
+            gravy_threshold: 0,
+            roll_model: RollModel::Exclusive,
+            simulations: 100000,
+            seed_offset: 0,
+            strategy: None,
+            histogram: None,
+            replay: None,
+            dump_scores: None,
+            regret: false,
+            win_matrix: false,
+            progress: false,
+            debug: false,
+            profile: false,
+            sixes: None,
+            #[cfg(feature = "serde")]
+            config: None,
+            verbose: false,
+            precision: 2,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    parsed.format = match args.next().as_deref() {
+                        Some("table") => Format::Table,
+                        Some("csv") => Format::Csv,
+                        #[cfg(feature = "serde")]
+                        Some("json") => Format::Json,
+                        _ => return Err(format!("--format expects one of: {}", FORMATS)),
+                    }
+                }
+                "--sort" | "--rank-by" => {
+                    parsed.sort = match args.next().as_deref() {
+                        Some("avg") => SortKey::Avg,
+                        Some("gravies") => SortKey::Gravies,
+                        Some("max") => SortKey::Max,
+                        Some("time") => SortKey::Time,
+                        Some("value") => SortKey::Value,
+                        _ => {
+                            return Err(format!(
+                                "{} expects one of: avg, gravies, max, time, value",
+                                arg
+                            ));
+                        }
+                    }
+                }
+                "--gravy-weight" => {
+                    let value = args.next().ok_or("--gravy-weight expects a number")?;
+                    parsed.gravy_weight = value
+                        .parse()
+                        .map_err(|_| format!("--gravy-weight expects a number, got '{}'", value))?;
+                }
+                "--gravy-threshold" => {
+                    let threshold = parse_number(&arg, args.next())?;
+                    parsed.gravy_threshold = u16::try_from(threshold)
+                        .map_err(|_| format!("--gravy-threshold expects at most {}", u16::MAX))?;
+                }
+                "--roll-model" => {
+                    parsed.roll_model = match args.next().as_deref() {
+                        Some("exclusive") => RollModel::Exclusive,
+                        Some("inclusive") => RollModel::Inclusive,
+                        _ => return Err("--roll-model expects one of: exclusive, inclusive".into()),
+                    }
+                }
+                "--simulations" => {
+                    parsed.simulations = parse_number(&arg, args.next())?;
+                    simulations_passed = true;
+                }
+                "--seed-offset" => parsed.seed_offset = parse_number(&arg, args.next())?,
+                "--strategy" => {
+                    let name = args
+                        .next()
+                        .ok_or("--strategy expects a strategy name or 'all'")?;
+                    parsed.strategy = if name == "all" { None } else { Some(name) };
+                    strategy_passed = true;
+                }
+                "--dump-scores" => {
+                    let path = args.next().ok_or("--dump-scores expects a path")?;
+                    parsed.dump_scores = Some(path);
+                }
+                "--replay" => parsed.replay = Some(parse_number(&arg, args.next())?),
+                "--regret" => parsed.regret = true,
+                "--win-matrix" => parsed.win_matrix = true,
+                "--progress" => parsed.progress = true,
+                "--debug" => parsed.debug = true,
+                "--profile" => parsed.profile = true,
+                "--sixes" => {
+                    let sixes = parse_number(&arg, args.next())?;
+                    if sixes == 0 {
+                        return Err("--sixes expects at least 1".to_string());
+                    }
+                    parsed.sixes = Some(sixes as usize);
+                }
+                #[cfg(feature = "serde")]
+                "--config" => {
+                    let path = args.next().ok_or("--config expects a path")?;
+                    parsed.config = Some(path);
+                }
+                "--verbose" | "-v" => parsed.verbose = true,
+                "--precision" => parsed.precision = parse_number(&arg, args.next())? as usize,
+                "--histogram" => {
+                    let name = args.next().ok_or("--histogram expects a strategy name")?;
+                    parsed.histogram = Some(name);
+                }
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
         }
-    }
 
-    fn eight() -> Self {
-        Die {
-            faces: Faces::Eight,
-            points: 8,
+        if !simulations_passed && let Some(value) = env("BISCUITS_SIMULATIONS") {
+            parsed.simulations = parse_number("BISCUITS_SIMULATIONS", Some(value))?;
+        }
+        if !strategy_passed && let Some(name) = env("BISCUITS_STRATEGY") {
+            parsed.strategy = if name == "all" { None } else { Some(name) };
         }
-    }
 
-    fn ten() -> Self {
-        Die {
-            faces: Faces::Ten,
-            points: 10,
+        if parsed.debug && parsed.profile {
+            return Err("--profile can't be combined with --debug".to_string());
+        }
+        // The timings only have a place in the table, so other formats would drop them silently
+        if parsed.profile && parsed.format != Format::Table {
+            return Err("--profile only works with --format table".to_string());
         }
-    }
 
-    fn twelve() -> Self {
-        Die {
-            faces: Faces::Twelve,
-            points: 12,
+        #[cfg(feature = "serde")]
+        if parsed.sixes.is_some() && parsed.config.is_some() {
+            return Err("--sixes can't be combined with --config".to_string());
         }
-    }
 
-    #[cfg(test)]
-    fn with_points(mut self, points: u8) -> Self {
-        self.points = points;
-        self
+        Ok(parsed)
     }
 
-    fn roll(&mut self, rng: &mut impl Rng) {
-        self.points = rng.random_range(0..self.faces.value());
+    fn seeds(&self) -> Range<u64> {
+        self.seed_offset..self.seed_offset.saturating_add(self.simulations)
     }
+}
+
+// The full game is too big for the oracle, so regret is measured on three d6s plus the big dice
+// unless --config picks another game
+const REGRET_COMPOSITION: [(Faces, usize); 4] = [
+    (Faces::SIX, 3),
+    (Faces::EIGHT, 1),
+    (Faces::TEN, 1),
+    (Faces::TWELVE, 1),
+];
+
+fn parse_number(flag: &str, value: Option<String>) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{} expects a number", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got '{}'", flag, value))
+}
+
+#[cfg(not(feature = "rayon"))]
+fn simulate(
+    mut strategy: BoxedStrategy,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> SimulationSummary {
+    biscuits::run_simulations_on_with_progress(&mut strategy, game, seeds, progress)
+}
 
-    fn points(&self) -> u8 {
-        self.points
+#[cfg(feature = "rayon")]
+fn simulate(
+    strategy: BoxedStrategy,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> SimulationSummary {
+    biscuits::run_simulations_par_on_with_progress(&strategy, game, seeds, progress)
+}
+
+// Like `simulate`, but plays one game at a time and names the seed and strategy before passing on
+// the first panic, so a strategy bug can be replayed
+fn simulate_debug(
+    name: &str,
+    mut strategy: BoxedStrategy,
+    game: &Game,
+    seeds: Range<u64>,
+    progress: &AtomicU64,
+) -> SimulationSummary {
+    match biscuits::run_simulations_on_catching(&mut strategy, game, seeds, progress) {
+        Ok(summary) => summary,
+        Err(panicked) => {
+            eprintln!(
+                "error: {} panicked on seed {}, replay it with --replay {} --strategy \"{}\"",
+                name, panicked.seed, panicked.seed, name
+            );
+            panic::resume_unwind(panicked.payload)
+        }
     }
 }
 
-#[cfg(test)]
-mod die_tests {
-    use super::*;
+// Runs `run` while keeping a count of the games it has played so far on stderr
+fn simulate_with_progress(
+    name: &str,
+    total: u64,
+    run: impl FnOnce(&AtomicU64) -> SimulationSummary,
+) -> SimulationSummary {
+    let progress = AtomicU64::new(0);
+    let finished = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            while !finished.load(Ordering::Relaxed) {
+                let played = progress.load(Ordering::Relaxed);
+                eprint!("\r{}: {}/{} games", name, played, total);
+                thread::park_timeout(Duration::from_millis(100));
+            }
+            eprintln!("\r{}: {}/{} games", name, total, total);
+        });
+
+        let summary = run(&progress);
+        finished.store(true, Ordering::Relaxed);
+        reporter.thread().unpark();
+        summary
+    })
+}
 
-    #[test]
-    fn test_points() {
-        let mut die = Die::six();
-        die.points = 2;
-        assert_eq!(die.points(), 2); // 6 - 4 = 2 points
+// Reads a game variant from a .toml file, or JSON otherwise
+#[cfg(feature = "serde")]
+fn load_game(path: &str) -> Result<Game, String> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path, e))?;
+    let config = if path.ends_with(".toml") {
+        biscuits::GameConfig::from_toml(&contents)
+    } else {
+        biscuits::GameConfig::from_json(&contents)
+    };
+
+    config
+        .and_then(|config| Game::from_config(&config))
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+// Colors are only for people looking at a terminal, not files or pipes, and can be turned off
+// with the NO_COLOR convention
+fn color_output() -> bool {
+    io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
 
-        die.points = 0;
-        assert_eq!(die.points(), 0); // 6 - 6 = 0 points
+// The game picked with --sixes or --config, if any
+fn configured_game(args: &Args) -> Option<Game> {
+    match args.sixes {
+        Some(sixes) => Some(Game::with_composition(&[
+            (Faces::SIX, sixes),
+            (Faces::EIGHT, 1),
+            (Faces::TEN, 1),
+            (Faces::TWELVE, 1),
+        ])),
+        None => loaded_game(args),
     }
 }
 
-// Game state
-struct Game {
-    dice: Vec<Die>,
+#[cfg(feature = "serde")]
+fn loaded_game(args: &Args) -> Option<Game> {
+    args.config.as_deref().map(|path| {
+        load_game(path).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        })
+    })
 }
 
-impl Game {
-    fn new() -> Self {
-        let mut dice = Vec::with_capacity(15);
+#[cfg(not(feature = "serde"))]
+fn loaded_game(_args: &Args) -> Option<Game> {
+    None
+}
 
-        // Add the 12 six-sided dice
-        for _ in 0..12 {
-            dice.push(Die::six());
-        }
+// Applies the flags that change how `game` is played and scored rather than which dice it has
+fn with_rules(args: &Args, game: Game) -> Game {
+    game.with_gravy_threshold(args.gravy_threshold)
+        .with_roll_model(args.roll_model)
+}
 
-        // Add the special dice
-        dice.push(Die::eight());
-        dice.push(Die::ten());
-        dice.push(Die::twelve());
+// Looks up a strategy by its display name
+fn find_strategy(name: &str) -> Result<(&'static str, BoxedStrategy), String> {
+    let strategies = all_strategies();
+    let names: Vec<String> = strategies.iter().map(|(n, _)| format!("'{}'", n)).collect();
+
+    strategies
+        .into_iter()
+        .find(|(n, _)| *n == name)
+        .ok_or_else(|| {
+            format!(
+                "unknown strategy: '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+// Modes that only make sense for one strategy need it picked with --strategy
+fn single_strategy(strategies: Vec<(&'static str, BoxedStrategy)>, flag: &str) -> BoxedStrategy {
+    let [(_, strategy)] = <[_; 1]>::try_from(strategies).unwrap_or_else(|_| {
+        eprintln!("error: {} needs --strategy <name>", flag);
+        process::exit(2);
+    });
+    strategy
+}
+
+fn print_histogram(histogram: &BTreeMap<u16, u64>) {
+    const BAR_WIDTH: usize = 60;
 
-        Game { dice }
+    let most = histogram.values().copied().max().unwrap_or(0);
+
+    for (points, count) in histogram {
+        let bar = (count * BAR_WIDTH as u64).div_ceil(most) as usize;
+        println!(
+            "{:>4} | {:<width$} {}",
+            points,
+            "#".repeat(bar),
+            count,
+            width = BAR_WIDTH
+        );
     }
+}
 
-    fn roll_all(&mut self, rng: &mut impl Rng) {
-        for die in &mut self.dice {
-            die.roll(rng);
+fn main() {
+    let env = |name: &str| std::env::var_os(name).map(|value| value.to_string_lossy().into_owned());
+    let args = match Args::parse(std::env::args().skip(1), env) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
         }
+    };
+
+    let num_simulations = args.simulations;
+
+    let strategies = match &args.strategy {
+        Some(name) => find_strategy(name).map(|strategy| vec![strategy]),
+        None => Ok(all_strategies()),
+    };
+    let strategies = strategies.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    });
+
+    let configured = configured_game(&args);
+    let game = with_rules(&args, configured.clone().unwrap_or_default());
+
+    if let Some(name) = &args.histogram {
+        let (_, mut strategy) = find_strategy(name).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        });
+
+        println!("Simulating {} games for {}...\n", num_simulations, name);
+        print_histogram(&biscuits::score_histogram_on(
+            &mut strategy,
+            &game,
+            args.seeds(),
+        ));
+        return;
     }
 
-    fn remove_dice(&mut self, indices: &mut [usize]) -> u8 {
-        // indices.sort_unstable_by(|a, b| b.cmp(a));
+    if let Some(seed) = args.replay {
+        let mut strategy = single_strategy(strategies, "--replay");
+        biscuits::replay_game_on(&mut strategy, &game, seed);
+        return;
+    }
+
+    if let Some(path) = &args.dump_scores {
+        let mut strategy = single_strategy(strategies, "--dump-scores");
+        let dumped = File::create(path).and_then(|file| {
+            biscuits::dump_scores_on(&mut strategy, &game, args.seeds(), BufWriter::new(file))
+        });
 
-        let mut points = 0;
-        for index in indices.iter().rev() {
-            points += self.dice.swap_remove(*index).points();
+        if let Err(e) = dumped {
+            eprintln!("error: couldn't write {}: {}", path, e);
+            process::exit(1);
         }
-        points
+        return;
     }
 
-    fn is_over(&self) -> bool {
-        self.dice.is_empty()
+    if args.win_matrix {
+        let mut strategies = strategies;
+        println!(
+            "Simulating {} games for each strategy...\n",
+            num_simulations
+        );
+        let matrix = biscuits::win_matrix_on(&mut strategies, &game, args.seeds());
+        let names: Vec<&str> = strategies.iter().map(|(name, _)| *name).collect();
+        print!("{}", format_win_matrix(&names, &matrix));
+        return;
     }
-}
 
-impl std::fmt::Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for die in self.dice.iter() {
-            write!(f, "{} ", die.points)?;
+    if args.regret {
+        let regret_game = configured.unwrap_or_else(|| Game::with_composition(&REGRET_COMPOSITION));
+        let game = with_rules(&args, regret_game);
+        if game.current_dice().len() > MAX_ORACLE_DICE {
+            eprintln!(
+                "error: --regret needs a game with at most {} dice",
+                MAX_ORACLE_DICE
+            );
+            process::exit(2);
         }
-        writeln!(f)?;
-        for die in self.dice.iter() {
-            write!(f, "{} ", die.faces.value())?;
+
+        println!("Measuring regret over {} games...\n", num_simulations);
+        println!("{:<30} {:>6}", "Strategy", "Regret");
+        for (name, mut strategy) in strategies {
+            let regret = biscuits::average_regret(&mut strategy, &game, args.seeds());
+            println!("{:<30} {:>6.2}", name, regret);
         }
+        return;
+    }
 
-        Ok(())
+    if args.format == Format::Table {
+        println!("Simulating {} games for each strategy...", num_simulations);
     }
-}
 
-// Define strategy as a type alias for a function that selects dice to remove
-type Strategy = fn(&[Die]) -> Vec<usize>;
+    let mut results = HashMap::new();
+    let mut deciding = HashMap::new();
+
+    for (name, mut strategy) in strategies {
+        let seeds = args.seeds();
+        let total = seeds.end - seeds.start;
+        let run = |progress: &AtomicU64| {
+            if args.debug {
+                simulate_debug(name, strategy, &game, seeds, progress)
+            } else if args.profile {
+                let (summary, elapsed) =
+                    biscuits::run_simulations_on_profiled(&mut strategy, &game, seeds, progress);
+                deciding.insert(name.to_string(), elapsed);
+                summary
+            } else {
+                simulate(strategy, &game, seeds, progress)
+            }
+        };
+        let summary = if args.progress {
+            simulate_with_progress(name, total, run)
+        } else {
+            run(&AtomicU64::new(0))
+        };
+        results.insert(name.to_string(), summary);
+    }
 
-// Strategy functions - each returns indices of dice to remove
+    // Sort and display results
+    let mut sorted_results: Vec<(&String, &SimulationSummary)> = results.iter().collect();
+    // The map has no order of its own, so ties, like every strategy with no games played, are
+    // listed by name instead of changing between runs
+    sorted_results.sort_by(|a, b| a.0.cmp(b.0));
+    sort_results(&mut sorted_results, args.sort, args.gravy_weight);
+
+    match args.format {
+        Format::Table => print!(
+            "\n{}",
+            format_table(
+                &sorted_results,
+                args.verbose,
+                args.precision,
+                color_output(),
+            )
+        ),
+        Format::Csv => print!("{}", format_csv(&sorted_results)),
+        #[cfg(feature = "serde")]
+        Format::Json => println!("{}", format_json(&sorted_results)),
+    }
 
-// Find all dice with zero points (maximum value rolled)
-fn find_zero_point_dice(dice: &[Die]) -> Vec<usize> {
-    dice.iter()
-        .enumerate()
-        .filter_map(|(i, die)| if die.points() == 0 { Some(i) } else { None })
-        .collect()
+    if args.profile {
+        print!("\n{}", format_profile(&sorted_results, &deciding));
+    }
 }
 
-fn find_big_zero_dice(dice: &[Die]) -> Vec<usize> {
-    dice.iter()
-        .enumerate()
-        .filter_map(|(i, die)| {
-            if die.points() == 0 && die.faces != Faces::Six {
-                Some(i)
-            } else {
-                None
-            }
-        })
-        .collect()
+fn sort_results(results: &mut [(&String, &SimulationSummary)], key: SortKey, gravy_weight: f64) {
+    match key {
+        SortKey::Avg => results.sort_by(|a, b| a.1.avg_points.total_cmp(&b.1.avg_points)),
+        SortKey::Gravies => results.sort_by_key(|(_, summary)| Reverse(summary.gravies)),
+        SortKey::Max => results.sort_by_key(|(_, summary)| summary.max_points),
+        SortKey::Time => results.sort_by_key(|(_, summary)| summary.duration),
+        SortKey::Value => results.sort_by(|a, b| {
+            score_value(a.1, gravy_weight).total_cmp(&score_value(b.1, gravy_weight))
+        }),
+    }
 }
 
-fn find_big_min_die(dice: &[Die]) -> usize {
-    dice.iter()
-        .enumerate()
-        .min_by(|(_, a), (_, b)| match a.points().cmp(&b.points()) {
-            Ordering::Equal => b.faces.cmp(&a.faces),
-            o => o,
+const TABLE_HEADER: [&str; 10] = [
+    "Strategy",
+    "Avg Points",
+    "StdDev",
+    "Min",
+    "Gravies",
+    "p90",
+    "Max",
+    "Rounds",
+    "Worst Seed",
+    "Time",
+];
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+// Print results in a nicely formatted table, with every column as wide as its widest cell. The
+// average is shown with `precision` decimals, and verbose tables add the 95% confidence interval
+// to it. With `color`, the rows with the best and worst averages are green and red, and so is
+// the most gravies.
+fn format_table(
+    results: &[(&String, &SimulationSummary)],
+    verbose: bool,
+    precision: usize,
+    color: bool,
+) -> String {
+    let rows: Vec<[String; 10]> = results
+        .iter()
+        .map(|(name, summary)| {
+            let avg = if verbose {
+                let (low, high) = summary.confidence_interval();
+                format!(
+                    "{:.*} ± {:.*}",
+                    precision,
+                    summary.avg_points,
+                    precision,
+                    (high - low) / 2.0
+                )
+            } else {
+                format!("{:.*}", precision, summary.avg_points)
+            };
+
+            [
+                name.to_string(),
+                avg,
+                format!("{:.2}", summary.std_dev),
+                summary.min_points.to_string(),
+                summary.gravies.to_string(),
+                summary.p90.to_string(),
+                summary.max_points.to_string(),
+                format!("{:.2}", summary.avg_rounds),
+                summary.worst_seed.to_string(),
+                format!("{:.2?}", summary.duration),
+            ]
         })
-        .unwrap()
-        .0
-}
-
-fn prio_min_for(die: &Die) -> i8 {
-    die.faces.value() as i8 - 4 * die.points() as i8
-}
+        .collect();
 
-// Prioritize removing high-sided dice when they have low points
-fn all_zero_or_prio_min_strategy(dice: &[Die]) -> Vec<usize> {
-    // First check for zero point dice
-    let zero_indices = find_zero_point_dice(dice);
-    if !zero_indices.is_empty() {
-        return zero_indices;
-    }
-
-    // Find the die with best score (higher max_value and lower points)
-    let mut best_index = 0;
-    let mut best_max = &Faces::Six;
-    let mut best_score = i8::MIN;
-
-    for (i, die) in dice.iter().enumerate() {
-        // Score function: higher is better - prioritize high max_value and low points
-        let score = prio_min_for(die);
-        if score > best_score || (score == best_score && die.faces > *best_max) {
-            best_score = score;
-            best_max = &die.faces;
-            best_index = i;
+    let mut widths = TABLE_HEADER.map(|header| header.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
         }
     }
+    let total_width = widths.iter().sum::<usize>() + widths.len() - 1;
+
+    let averages = results.iter().map(|(_, summary)| summary.avg_points);
+    let best = averages.clone().min_by(f64::total_cmp);
+    let worst = averages.max_by(f64::total_cmp);
+    let most_gravies = results.iter().map(|(_, summary)| summary.gravies).max();
+
+    let mut out = format_row(&TABLE_HEADER, &widths, &[None; 10]);
+    out += &format!("{:-<1$}\n", "", total_width);
+    for (row, (_, summary)) in rows.iter().zip(results) {
+        let mut colors = [None; 10];
+        // With a single row there's nothing to compare against
+        if color && results.len() > 1 {
+            if Some(summary.avg_points) == best {
+                colors = [Some(GREEN); 10];
+            } else if Some(summary.avg_points) == worst {
+                colors = [Some(RED); 10];
+            }
+            if summary.gravies > 0 && Some(summary.gravies) == most_gravies {
+                colors[4] = Some(GREEN);
+            }
+        }
+
+        out += &format_row(row, &widths, &colors);
+    }
 
-    vec![best_index]
+    out
 }
 
-fn all_zero_or_big_min_strategy(dice: &[Die]) -> Vec<usize> {
-    // First check for zero point dice
-    let zero_indices = find_zero_point_dice(dice);
-    if !zero_indices.is_empty() {
-        return zero_indices;
+// Left-aligns the name in the first cell and right-aligns everything else. Colors are added
+// around the padded cells so they don't count towards the width.
+fn format_row<S: AsRef<str>>(cells: &[S], widths: &[usize], colors: &[Option<&str>]) -> String {
+    let mut out = String::new();
+
+    for (i, ((cell, width), color)) in cells.iter().zip(widths).zip(colors).enumerate() {
+        let cell = if i == 0 {
+            format!("{:<width$}", cell.as_ref())
+        } else {
+            out += " ";
+            format!("{:>width$}", cell.as_ref())
+        };
+
+        match color {
+            Some(color) => out += &format!("{}{}{}", color, cell, RESET),
+            None => out += &cell,
+        }
     }
 
-    vec![find_big_min_die(dice)]
+    out + "\n"
 }
 
-fn all_big_zero_or_one_zero_or_big_min_strategy(dice: &[Die]) -> Vec<usize> {
-    let big_zeros = find_big_zero_dice(dice);
-    if !big_zeros.is_empty() {
-        let big_dice_count = dice.iter().filter(|die| die.faces != Faces::Six).count();
-        let big_zero_count = big_zeros.len();
+// Prints how often each row's strategy beat each column's, with ties counting as half. Columns
+// are numbered to keep them narrow, matching the numbers in front of the rows.
+fn format_win_matrix(names: &[&str], matrix: &[Vec<f64>]) -> String {
+    let name_width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        + 4;
+
+    let mut out = format!("{:name_width$}", "");
+    for column in 1..=names.len() {
+        out += &format!(" {:>6}", column);
+    }
+    out += "\n";
 
-        if big_dice_count == big_zero_count {
-            return find_zero_point_dice(dice);
+    for (row, (name, shares)) in names.iter().zip(matrix).enumerate() {
+        out += &format!("{:<name_width$}", format!("{:>2}. {}", row + 1, name));
+        for share in shares {
+            out += &format!(" {:>5.1}%", share * 100.0);
         }
-        return big_zeros;
+        out += "\n";
     }
 
-    let all_zeros = find_zero_point_dice(dice);
-    if !all_zeros.is_empty() {
-        if dice.iter().filter(|die| die.faces != Faces::Six).count() == 0 {
-            return all_zeros;
+    out
+}
+
+// How much of each strategy's run went to choosing dice, in the same order as the results
+fn format_profile(
+    results: &[(&String, &SimulationSummary)],
+    deciding: &HashMap<String, Duration>,
+) -> String {
+    let mut out = format!("{:<30} {:>10} {:>6}\n", "Strategy", "Deciding", "Share");
+
+    for (name, summary) in results {
+        let elapsed = deciding[*name];
+        // A run too short to measure has no time to share out
+        let share = if summary.duration.is_zero() {
+            0.0
         } else {
-            return vec![all_zeros[0]];
-        }
+            elapsed.as_secs_f64() / summary.duration.as_secs_f64()
+        };
+        out += &format!(
+            "{:<30} {:>10} {:>5.1}%\n",
+            name,
+            format!("{:.2?}", elapsed),
+            share * 100.0
+        );
     }
 
-    vec![find_big_min_die(dice)]
+    out
 }
 
-const FETTERMANIA_CUTOFF_06: [u8; 16] = [2, 2, 2, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-const FETTERMANIA_CUTOFF_08: [u8; 16] = [3, 3, 3, 2, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0];
-const FETTERMANIA_CUTOFF_10: [u8; 16] = [4, 4, 4, 3, 2, 2, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
-const FETTERMANIA_CUTOFF_12: [u8; 16] = [5, 5, 5, 4, 3, 2, 2, 1, 1, 1, 1, 1, 1, 0, 0, 0];
-
-fn fettermania_cutoff(faces: &Faces, dice_left: usize) -> u8 {
-    match faces {
-        Faces::Six => FETTERMANIA_CUTOFF_06[dice_left],
-        Faces::Eight => FETTERMANIA_CUTOFF_08[dice_left],
-        Faces::Ten => FETTERMANIA_CUTOFF_10[dice_left],
-        Faces::Twelve => FETTERMANIA_CUTOFF_12[dice_left],
+fn format_csv(results: &[(&String, &SimulationSummary)]) -> String {
+    let mut out = "name,avg_points,min_points,max_points,gravies,duration_ms\n".to_string();
+
+    for (name, summary) in results {
+        out += &format!(
+            "{},{},{},{},{},{:.3}\n",
+            csv_field(name),
+            summary.avg_points,
+            summary.min_points,
+            summary.max_points,
+            summary.gravies,
+            summary.duration.as_secs_f64() * 1000.0
+        );
     }
+
+    out
 }
 
-fn fettermania_blackjack_strategy(dice: &[Die]) -> Vec<usize> {
-    let dice_left = dice.len();
+#[cfg(feature = "serde")]
+fn format_json(results: &[(&String, &SimulationSummary)]) -> String {
+    #[derive(serde::Serialize)]
+    struct NamedSummary<'a> {
+        name: &'a str,
+        #[serde(flatten)]
+        summary: &'a SimulationSummary,
+    }
 
-    let cutoff_candidates: Vec<usize> = dice
+    let named: Vec<NamedSummary> = results
         .iter()
-        .enumerate()
-        .filter_map(|(i, die)| {
-            let cutoff = fettermania_cutoff(&die.faces, dice_left);
-            if die.points() <= cutoff {
-                Some(i)
-            } else {
-                None
-            }
-        })
+        .map(|(name, summary)| NamedSummary { name, summary })
         .collect();
 
-    if !cutoff_candidates.is_empty() {
-        return cutoff_candidates;
-    }
-
-    // Interestingly, the paper suggests that breaking ties here is arbitrary, but based on my
-    // tests the avg improves from 8.53 when preferring small dice to 8.48 when preferring large
-    // dice
-    vec![
-        dice.iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| {
-                let a_val = a.points() - fettermania_cutoff(&a.faces, dice_left);
-                let b_val = b.points() - fettermania_cutoff(&b.faces, dice_left);
+    serde_json::to_string_pretty(&named).unwrap()
+}
 
-                match a_val.cmp(&b_val) {
-                    Ordering::Equal => b.faces.cmp(&a.faces),
-                    o => o,
-                }
-            })
-            .unwrap()
-            .0,
-    ]
+// Quotes a field if it would otherwise break the row apart
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
 }
 
 #[cfg(test)]
-mod func_tests {
+mod tests {
     use super::*;
+    use std::time::Duration;
 
-    #[test]
-    fn test_find_zero_point_dice() {
-        let dice = vec![
-            Die::six().with_points(0),
-            Die::six().with_points(3),
-            Die::eight().with_points(0),
-            Die::ten().with_points(1),
-        ];
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), |_| None)
+    }
 
-        let zero_indices = find_zero_point_dice(&dice);
-        assert_eq!(zero_indices, vec![0, 2]); // Only indices 0 and 2 have zero points
+    fn summary() -> SimulationSummary {
+        SimulationSummary {
+            games: 100000,
+            avg_points: 8.5,
+            std_dev: 3.5,
+            std_error: 0.05,
+            min_points: 0,
+            median: 8,
+            p90: 13,
+            p99: 20,
+            max_points: 30,
+            best_seed: 7,
+            worst_seed: 4021,
+            gravies: 100,
+            longest_gravy_streak: 2,
+            skewness: 0.5,
+            kurtosis: 0.25,
+            avg_rounds: 12.25,
+            duration: Duration::from_micros(105_330),
+        }
     }
 
     #[test]
-    fn test_find_big_min_die() {
-        let mut dice = vec![Die::six().with_points(1), Die::ten().with_points(1)];
-
-        let min_index = find_big_min_die(&dice);
-        assert_eq!(1, min_index);
-
-        dice.reverse();
+    fn test_parse_defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(0..100000, args.seeds());
+        assert_eq!(None, args.strategy);
+        assert_eq!(None, args.histogram);
+        assert_eq!(None, args.replay);
+    }
 
-        let min_index = find_big_min_die(&dice);
-        assert_eq!(0, min_index);
+    #[test]
+    fn test_parse_strategy() {
+        let args = parse(&["--strategy", "All Zero/Prio Min"]).unwrap();
+        assert_eq!(Some("All Zero/Prio Min"), args.strategy.as_deref());
+
+        let args = parse(&["--strategy", "all"]).unwrap();
+        assert_eq!(None, args.strategy);
+
+        let args = parse(&["--replay", "800", "--strategy", "One Max"]).unwrap();
+        assert_eq!(Some(800), args.replay);
+
+        let args = parse(&["--dump-scores", "scores.bin"]).unwrap();
+        assert_eq!(Some("scores.bin"), args.dump_scores.as_deref());
+
+        assert!(!parse(&[]).unwrap().regret);
+        assert!(parse(&["--regret"]).unwrap().regret);
+        assert!(parse(&["--progress"]).unwrap().progress);
+        assert!(!parse(&[]).unwrap().debug);
+        assert!(parse(&["--debug"]).unwrap().debug);
+        assert!(parse(&["--profile"]).unwrap().profile);
+        assert!(parse(&["--profile", "--debug"]).is_err());
+        assert!(parse(&["--profile", "--format", "table"]).unwrap().profile);
+        assert!(parse(&["--format", "csv", "--profile"]).is_err());
     }
 
     #[test]
-    fn test_all_zero_or_prio_min_strategy() {
-        let mut dice = vec![
-            Die::six().with_points(3),
-            Die::eight().with_points(1),
-            Die::ten().with_points(4),
-            Die::twelve().with_points(2),
-        ];
+    fn test_format_profile() {
+        let name = "All Zero/Big Min".to_string();
+        let mut summary = summary();
+        summary.duration = Duration::from_millis(80);
+        let deciding = HashMap::from([(name.clone(), Duration::from_millis(20))]);
+
+        assert_eq!(
+            "Strategy                         Deciding  Share\n\
+             All Zero/Big Min                  20.00ms  25.0%\n",
+            format_profile(&[(&name, &summary)], &deciding)
+        );
 
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![3], prio_min); // 12 - 4 * 2 = 4
+        summary.duration = Duration::ZERO;
+        let deciding = HashMap::from([(name.clone(), Duration::ZERO)]);
+        assert_eq!(
+            "Strategy                         Deciding  Share\n\
+             All Zero/Big Min                   0.00ns   0.0%\n",
+            format_profile(&[(&name, &summary)], &deciding)
+        );
+    }
 
-        dice.remove(prio_min[0]);
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![1], prio_min); // 8 - 4 * 1 = 4
+    #[test]
+    fn test_env_fallbacks() {
+        let env = |name: &str| match name {
+            "BISCUITS_SIMULATIONS" => Some("500".to_string()),
+            "BISCUITS_STRATEGY" => Some("One Max".to_string()),
+            _ => None,
+        };
+        let parse_env = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()), env);
+
+        let args = parse_env(&[]).unwrap();
+        assert_eq!(500, args.simulations);
+        assert_eq!(Some("One Max"), args.strategy.as_deref());
+
+        // Flags win over the environment
+        let args = parse_env(&["--simulations", "20", "--strategy", "all"]).unwrap();
+        assert_eq!(20, args.simulations);
+        assert_eq!(None, args.strategy);
+
+        // A bad value in the environment is an error, not the default
+        let bad = |name: &str| (name == "BISCUITS_SIMULATIONS").then(|| "lots".to_string());
+        assert_eq!(
+            "BISCUITS_SIMULATIONS expects a number, got 'lots'",
+            Args::parse(std::iter::empty(), bad).err().unwrap()
+        );
+        // Unless a flag means it's never read
+        let args = Args::parse(
+            ["--simulations".to_string(), "7".to_string()].into_iter(),
+            bad,
+        );
+        assert_eq!(7, args.unwrap().simulations);
 
-        dice.remove(prio_min[0]);
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![1], prio_min); // 10 - 4 * 4 = -6
+        let all = |name: &str| (name == "BISCUITS_STRATEGY").then(|| "all".to_string());
+        assert_eq!(None, Args::parse(std::iter::empty(), all).unwrap().strategy);
+    }
 
-        dice.remove(prio_min[0]);
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![0], prio_min); // 6 - 4 * 3 = -6
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_with_rules() {
+        let path = std::env::temp_dir().join(format!("biscuits-config-{}.toml", process::id()));
+        std::fs::write(&path, "[[dice]]\nfaces = 20\ncount = 2\n").unwrap();
+
+        let args = parse(&[
+            "--config",
+            path.to_str().unwrap(),
+            "--roll-model",
+            "inclusive",
+        ])
+        .unwrap();
+        let game = with_rules(&args, configured_game(&args).unwrap());
+        assert_eq!(0, game.gravy_threshold());
+        std::fs::remove_file(&path).unwrap();
+
+        // The loaded dice are kept, with the rules from the other flags on top
+        assert_eq!(2, game.face_counts()[&Faces::TWENTY]);
+        assert_eq!(RollModel::Inclusive, game.roll_model());
     }
 
     #[test]
-    fn test_all_zero_or_prio_min_strategy_hmm() {
-        let mut dice = vec![Die::six().with_points(1), Die::twelve().with_points(3)];
+    fn test_sixes() {
+        let game = configured_game(&parse(&["--sixes", "5"]).unwrap()).unwrap();
+        assert_eq!(8, game.current_dice().len());
+        assert_eq!(5, game.face_counts()[&Faces::SIX]);
+
+        let game = configured_game(&parse(&["--sixes", "12"]).unwrap()).unwrap();
+        assert_eq!(Game::new().to_string(), game.to_string());
+        assert!(configured_game(&parse(&[]).unwrap()).is_none());
+
+        assert_eq!(
+            "--sixes expects at least 1",
+            parse(&["--sixes", "0"]).err().unwrap()
+        );
+        assert!(parse(&["--sixes", "many"]).is_err());
+        #[cfg(feature = "serde")]
+        assert!(parse(&["--sixes", "5", "--config", "game.toml"]).is_err());
+    }
 
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![0], prio_min); // 6 - 4 * 1 = 2
+    #[test]
+    fn test_find_strategy() {
+        for (name, _) in all_strategies() {
+            assert_eq!(name, find_strategy(name).unwrap().0);
+        }
 
-        dice.remove(prio_min[0]);
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(vec![0], prio_min); // 12 - 4 * 3 = 0
+        let err = find_strategy("Max Zero").err().unwrap();
+        assert!(
+            err.starts_with("unknown strategy: 'Max Zero', expected one of: 'All Zero/Big Min'")
+        );
     }
 
     #[test]
-    fn test_all_zero_or_prio_min_deterministic() {
-        let mut dice = vec![Die::eight().with_points(1), Die::twelve().with_points(2)];
-        // 8 - 4 * 1 = 4
-        // 12 - 4 * 2 = 4
-        dice.iter().for_each(|d| assert_eq!(4, prio_min_for(d)));
-
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(12, dice[prio_min[0]].faces.value());
+    fn test_parse_simulations() {
+        let args = parse(&["--simulations", "5000", "--seed-offset", "200"]).unwrap();
+        assert_eq!(200..5200, args.seeds());
 
-        dice.reverse();
+        let err = parse(&["--simulations", "lots"]).err().unwrap();
+        assert_eq!("--simulations expects a number, got 'lots'", err);
 
-        let prio_min = all_zero_or_prio_min_strategy(&dice);
-        assert_eq!(12, dice[prio_min[0]].faces.value());
+        let err = parse(&["--seed-offset"]).err().unwrap();
+        assert_eq!("--seed-offset expects a number", err);
     }
 
     #[test]
-    fn test_game_remove_dice() {
-        let mut game = Game::new();
-        game.dice = vec![
-            Die::six().with_points(3),
-            Die::eight().with_points(1),
-            Die::ten().with_points(5),
-            Die::twelve().with_points(2),
-        ];
-
-        let points = game.remove_dice(&mut [1, 3]);
-        assert_eq!(points, 3); // 1 + 2 = 3 points
-        assert_eq!(game.dice.len(), 2); // Should have 2 dice left
-        assert_eq!(game.dice[0].points, 3); // First die should remain
-        assert_eq!(game.dice[1].points, 5); // Third die should remain
+    fn test_parse_format() {
+        assert_eq!(Format::Table, parse(&[]).unwrap().format);
+        assert_eq!(Format::Csv, parse(&["--format", "csv"]).unwrap().format);
+        assert!(parse(&["--format", "xml"]).is_err());
     }
 
     #[test]
-    fn test_full_game_simulation() {
-        let points = simulate_game(all_zero_or_big_min_strategy, 800);
-        assert_eq!(0, points);
-    }
-}
+    fn test_sort_results() {
+        let names = ["A", "B", "C", "D"].map(String::from);
+        let summaries = [
+            (9.1, 40, 31),
+            (8.4, 100, 30),
+            (f64::NAN, 0, 0),
+            (8.9, 440, 30),
+        ]
+        .map(|(avg_points, gravies, max_points)| SimulationSummary {
+            avg_points,
+            gravies,
+            max_points,
+            ..summary()
+        });
+        let mut results: Vec<(&String, &SimulationSummary)> =
+            names.iter().zip(&summaries).collect();
+        let order = |results: &[(&String, &SimulationSummary)]| -> String {
+            results.iter().map(|(name, _)| name.as_str()).collect()
+        };
+
+        sort_results(&mut results, SortKey::Gravies, 1.0);
+        assert_eq!("DBAC", order(&results));
+
+        sort_results(&mut results, SortKey::Max, 1.0);
+        assert_eq!("CDBA", order(&results));
+
+        // NaN sorts last instead of panicking
+        sort_results(&mut results, SortKey::Avg, 1.0);
+        assert_eq!("BDAC", order(&results));
+
+        assert_eq!(SortKey::Time, parse(&["--sort", "time"]).unwrap().sort);
+        assert!(parse(&["--sort", "name"]).is_err());
+
+        // D gives up 0.5 points on average to B for 0.34% more gravies, which only pays off once
+        // gravies are worth enough
+        sort_results(&mut results, SortKey::Value, 1.0);
+        assert_eq!("BDAC", order(&results));
+        sort_results(&mut results, SortKey::Value, 10.0);
+        assert_eq!("DBAC", order(&results));
+
+        // With no games played everything ties, and sorting keeps the order it was given
+        let empty = biscuits::run_simulations(&mut biscuits::FnStrategy(|_| Vec::new()), 0);
+        let names = ["B", "A", "C"].map(String::from);
+        let mut results: Vec<(&String, &SimulationSummary)> =
+            names.iter().map(|name| (name, &empty)).collect();
+        for key in [SortKey::Avg, SortKey::Gravies, SortKey::Max, SortKey::Value] {
+            sort_results(&mut results, key, 1.0);
+            assert_eq!("BAC", order(&results));
+        }
 
-fn simulate_game(strategy: Strategy, seed: u64) -> u8 {
-    let mut rng = SmallRng::seed_from_u64(seed);
-    let mut game = Game::new();
-    let mut total_points = 0;
+        let args = parse(&["--rank-by", "value", "--gravy-weight", "5.0"]).unwrap();
+        assert_eq!((SortKey::Value, 5.0), (args.sort, args.gravy_weight));
+        assert!(parse(&["--gravy-weight", "lots"]).is_err());
 
-    while !game.is_over() {
-        game.roll_all(&mut rng);
-        // if seed == 800 {
-        //     println!("{}\n", game);
-        // }
-        let mut indices = strategy(&game.dice);
-        total_points += game.remove_dice(&mut indices);
+        assert_eq!(0, parse(&[]).unwrap().gravy_threshold);
+        let args = parse(&["--gravy-threshold", "5"]).unwrap();
+        assert_eq!(5, args.gravy_threshold);
+        assert_eq!(5, with_rules(&args, Game::new()).gravy_threshold());
+        assert!(parse(&["--gravy-threshold", "70000"]).is_err());
     }
 
-    total_points
-}
-
-fn run_simulations(strategy: Strategy, num_simulations: u64) -> (f64, u8, u64, u8) {
-    let mut total_points = 0;
-    let mut gravies = 0;
-    let mut min_points = u8::MAX;
-    let mut max_points = 0;
-
-    for i in 0..num_simulations {
-        let points = simulate_game(strategy, i);
-        total_points += points as u64;
-        if points == 0 {
-            gravies += 1;
-            // println!("seed: {}", i);
-        }
-        min_points = min_points.min(points);
-        max_points = max_points.max(points);
+    #[test]
+    fn test_roll_model() {
+        assert_eq!(RollModel::Exclusive, parse(&[]).unwrap().roll_model);
+        let args = parse(&["--roll-model", "inclusive"]).unwrap();
+        assert_eq!(RollModel::Inclusive, args.roll_model);
+        assert!(parse(&["--roll-model", "fair"]).is_err());
+        assert!(parse(&["--roll-model"]).is_err());
     }
 
-    let avg_points = total_points as f64 / num_simulations as f64;
-    (avg_points, min_points, gravies, max_points)
-}
-
-fn main() {
-    let num_simulations: u64 = 100000;
-
-    let strategies: Vec<(String, Strategy)> = vec![
-        ("All Zero/Big Min".to_string(), all_zero_or_big_min_strategy),
-        (
-            "All Big Zero/One Zero/Big Min".to_string(),
-            all_big_zero_or_one_zero_or_big_min_strategy,
-        ),
-        (
-            "All Zero/Prio Min".to_string(),
-            all_zero_or_prio_min_strategy,
-        ),
-        (
-            "Fettermania Blackjack".to_string(),
-            fettermania_blackjack_strategy,
-        ),
-    ];
-
-    println!("Simulating {} games for each strategy...", num_simulations);
+    #[test]
+    fn test_format_table_verbose() {
+        let summary = summary();
+        let name = "Fettermania Blackjack".to_string();
+
+        let plain = format_table(&[(&name, &summary)], false, 2, false);
+        let verbose = format_table(&[(&name, &summary)], true, 2, false);
+        let precise = format_table(&[(&name, &summary)], true, 3, false);
+
+        assert!(plain.contains("Fettermania Blackjack       8.50 "));
+        assert!(verbose.contains("Fettermania Blackjack 8.50 ± 0.10 "));
+        assert!(precise.contains("Fettermania Blackjack 8.500 ± 0.098 "));
+        for table in [plain, verbose, precise] {
+            let lines: Vec<&str> = table.lines().collect();
+            assert_eq!(lines[0].chars().count(), lines[1].chars().count());
+            assert_eq!(lines[0].chars().count(), lines[2].chars().count());
+        }
 
-    // Type alias for the result type to reduce complexity
-    type SimulationResult = (f64, u8, u64, u8, std::time::Duration);
+        assert_eq!(3, parse(&["--precision", "3"]).unwrap().precision);
+    }
 
-    let mut results = HashMap::new();
+    #[test]
+    fn test_format_row_long_name() {
+        let long = "All Zero Then Whatever Die Has The Lowest Points".to_string();
+        let short = "One Max".to_string();
+        let summary = summary();
+
+        let table = format_table(&[(&long, &summary), (&short, &summary)], false, 2, false);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[2].starts_with(&format!("{} ", long)));
+        assert!(lines[3].starts_with(&format!("{:<1$} ", short, long.len())));
+        // Every row is as wide as the rule, and the columns after the name line up
+        for line in &lines {
+            assert_eq!(lines[1].len(), line.chars().count());
+        }
+        assert_eq!(lines[2][long.len()..], lines[3][long.len()..]);
+        assert!(lines[0].ends_with("     Time"));
+        assert!(lines[2].ends_with(" 105.33ms"));
+    }
 
-    for (name, strategy) in strategies {
-        let start = Instant::now();
-        let (avg_points, min_points, gravies, max_points) =
-            run_simulations(strategy, num_simulations);
-        let duration = start.elapsed();
+    #[test]
+    fn test_format_table_color() {
+        let names = ["Best", "Middle", "Worst"].map(String::from);
+        let summaries =
+            [(8.5, 100), (9.0, 400), (74.2, 0)].map(|(avg_points, gravies)| SimulationSummary {
+                avg_points,
+                gravies,
+                ..summary()
+            });
+        let results: Vec<(&String, &SimulationSummary)> = names.iter().zip(&summaries).collect();
+
+        let plain = format_table(&results, false, 2, false);
+        assert!(!plain.contains('\x1b'));
+
+        let colored = format_table(&results, false, 2, true);
+        let lines: Vec<&str> = colored.lines().collect();
+        assert!(lines[2].starts_with(&format!("{}Best", GREEN)));
+        assert!(lines[3].starts_with("Middle"));
+        assert!(lines[3].contains(&format!("{}    400{}", GREEN, RESET)));
+        assert!(lines[4].starts_with(&format!("{}Worst", RED)));
+
+        // Stripping the colors leaves the plain table
+        let stripped = colored
+            .replace(GREEN, "")
+            .replace(RED, "")
+            .replace(RESET, "");
+        assert_eq!(plain, stripped);
+    }
 
-        results.insert(
-            name,
-            (avg_points, min_points, gravies, max_points, duration),
+    #[test]
+    fn test_format_win_matrix() {
+        let matrix = vec![vec![0.5, 0.75], vec![0.25, 0.5]];
+
+        assert_eq!(
+            "                  1      2\n\
+             \x201. Prio Min  50.0%  75.0%\n\
+             \x202. One Max   25.0%  50.0%\n",
+            format_win_matrix(&["Prio Min", "One Max"], &matrix)
         );
+        assert!(parse(&["--win-matrix"]).unwrap().win_matrix);
     }
 
-    // Print results in a nicely formatted table
-    println!(
-        "\n{:<30} {:<10} {:>4} {:>8} {:>4} {:>10}",
-        "Strategy", "Avg Points", "Min", "Gravies", "Max", "Time"
-    );
-    println!("{:-<72}", "");
-
-    // Sort and display results
-    let mut sorted_results: Vec<(&String, &SimulationResult)> = results.iter().collect();
-    sorted_results.sort_by(|a, b| a.1.0.partial_cmp(&b.1.0).unwrap());
-
-    for (name, (avg, min, gravies, max, duration)) in sorted_results {
-        println!(
-            "{:<30} {:>10.2} {:>4} {:>8} {:>4} {:>10.2?}",
-            name, avg, min, gravies, max, duration
+    #[test]
+    fn test_format_csv() {
+        let summary = summary();
+        let plain = "Fettermania Blackjack".to_string();
+        let quoted = "Zero, Then \"Min\"".to_string();
+
+        assert_eq!(
+            "name,avg_points,min_points,max_points,gravies,duration_ms\n\
+             Fettermania Blackjack,8.5,0,30,100,105.330\n\
+             \"Zero, Then \"\"Min\"\"\",8.5,0,30,100,105.330\n",
+            format_csv(&[(&plain, &summary), (&quoted, &summary)])
         );
     }
 }
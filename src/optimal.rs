@@ -0,0 +1,175 @@
+use crate::die::{Die, Faces};
+use crate::strategy::Strategy;
+use std::collections::HashMap;
+
+/// Plays perfectly by minimizing the expected final score.
+///
+/// Every decision weighs the points taken now against the expected score of the dice left behind,
+/// which is computed by enumerating every roll and every removal of the smaller boards. That is
+/// only tractable for a handful of dice, so this is meant as a baseline for small compositions
+/// rather than something to run against the full game.
+#[derive(Clone, Debug, Default)]
+pub struct OptimalStrategy {
+    // Expected points still to come when rolling a sorted set of dice
+    memo: HashMap<Vec<Faces>, f64>,
+}
+
+impl OptimalStrategy {
+    /// The most dice a board may have. Beyond this there are too many outcomes to enumerate.
+    pub const MAX_DICE: usize = 6;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the expected final score of rolling `faces` and playing perfectly from there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than [`MAX_DICE`](Self::MAX_DICE) dice.
+    pub fn expected_score(&mut self, faces: &[Faces]) -> f64 {
+        assert!(
+            faces.len() <= Self::MAX_DICE,
+            "optimal strategy only supports up to {} dice",
+            Self::MAX_DICE
+        );
+
+        let mut faces = faces.to_vec();
+        faces.sort();
+        self.expected_sorted(faces)
+    }
+
+    fn expected_sorted(&mut self, faces: Vec<Faces>) -> f64 {
+        if faces.is_empty() {
+            return 0.0;
+        }
+        if let Some(expected) = self.memo.get(&faces) {
+            return *expected;
+        }
+
+        let keep_costs = self.keep_costs(&faces);
+        let mut points = vec![0; faces.len()];
+        let mut total = 0.0;
+        let mut outcomes = 0;
+
+        'rolls: loop {
+            total += best_removal(&points, &keep_costs).1;
+            outcomes += 1;
+
+            // Step to the next roll, like an odometer
+            for (i, faces) in faces.iter().enumerate() {
+                points[i] += 1;
+                if points[i] < faces.value() {
+                    continue 'rolls;
+                }
+                points[i] = 0;
+            }
+            break;
+        }
+
+        let expected = total / outcomes as f64;
+        self.memo.insert(faces, expected);
+        expected
+    }
+
+    // Expected score of the dice left behind, indexed by the bitmask of dice kept. At least one
+    // die has to be removed, so keeping all of them isn't included.
+    fn keep_costs(&mut self, faces: &[Faces]) -> Vec<f64> {
+        (0..(1usize << faces.len()) - 1)
+            .map(|kept| {
+                let mut kept: Vec<Faces> = faces
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| kept & (1 << i) != 0)
+                    .map(|(_, faces)| *faces)
+                    .collect();
+                kept.sort();
+                self.expected_sorted(kept)
+            })
+            .collect()
+    }
+}
+
+// Finds the bitmask of dice to remove that minimizes the points taken now plus the expected score
+// of the dice kept. Ties go to the lowest mask.
+fn best_removal(points: &[u8], keep_costs: &[f64]) -> (usize, f64) {
+    let all = keep_costs.len();
+
+    (1..=all)
+        .map(|removed| {
+            let taken: u32 = points
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| removed & (1 << i) != 0)
+                .map(|(_, points)| *points as u32)
+                .sum();
+            (removed, taken as f64 + keep_costs[all & !removed])
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap()
+}
+
+impl Strategy for OptimalStrategy {
+    /// # Panics
+    ///
+    /// Panics if there are more than [`MAX_DICE`](Self::MAX_DICE) dice.
+    fn select(&mut self, dice: &[Die]) -> Vec<usize> {
+        if dice.is_empty() {
+            return Vec::new();
+        }
+        assert!(
+            dice.len() <= Self::MAX_DICE,
+            "optimal strategy only supports up to {} dice",
+            Self::MAX_DICE
+        );
+
+        let faces: Vec<Faces> = dice.iter().map(|die| die.faces).collect();
+        let points: Vec<u8> = dice.iter().map(|die| die.points()).collect();
+        let (removed, _) = best_removal(&points, &self.keep_costs(&faces));
+
+        (0..dice.len())
+            .filter(|i| removed & (1 << i) != 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_score_one_d6() {
+        let mut strategy = OptimalStrategy::new();
+        assert_eq!(2.5, strategy.expected_score(&[Faces::SIX]));
+    }
+
+    #[test]
+    fn test_expected_score_two_d6() {
+        let mut strategy = OptimalStrategy::new();
+
+        // A lone d6 is worth 2.5, so out of a pair you take the lower die and keep the higher one
+        // only when it shows 3 or more. Summing min + min(max, 2.5) over all 36 rolls gives 271/2.
+        let expected = strategy.expected_score(&[Faces::SIX, Faces::SIX]);
+        assert!((expected - 271.0 / 72.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_select() {
+        let mut strategy = OptimalStrategy::new();
+
+        // 1 + 2.5 beats taking both for 5
+        let dice = vec![Die::six().with_points(1), Die::six().with_points(4)];
+        assert_eq!(vec![0], strategy.select(&dice));
+
+        // 1 + 2 beats 1 + 2.5
+        let dice = vec![Die::six().with_points(2), Die::six().with_points(1)];
+        assert_eq!(vec![0, 1], strategy.select(&dice));
+    }
+
+    #[test]
+    #[should_panic(expected = "only supports up to 6 dice")]
+    fn test_rejects_full_game() {
+        let faces: Vec<Faces> = crate::Game::new().dice.iter().map(|d| d.faces).collect();
+        OptimalStrategy::new().expected_score(&faces);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::die::Faces;
+use crate::game::Game;
+use crate::strategy::Strategy;
+use crate::{play, select_dice};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The most dice [`best_achievable_score`] will search. Every extra die multiplies the number of
+/// ways to play a game by roughly the number of dice, so the full game is far out of reach.
+pub const MAX_ORACLE_DICE: usize = 8;
+
+/// Returns the lowest score possible on `seed` for someone who knows every roll in advance.
+///
+/// This plays `game` from the start with the same generator [`simulate_game`](crate::simulate_game)
+/// would, trying every way to remove at least one die each round. No real strategy can see the
+/// future, so no strategy that always removes something can beat this on the same seed, and the
+/// difference is how much that strategy left on the table.
+///
+/// # Panics
+///
+/// Panics if `game` starts with more than [`MAX_ORACLE_DICE`] dice.
+pub fn best_achievable_score(game: &Game, seed: u64) -> u16 {
+    let mut game = game.clone();
+    game.reset();
+    assert!(
+        game.current_dice().len() <= MAX_ORACLE_DICE,
+        "the oracle only supports up to {} dice",
+        MAX_ORACLE_DICE
+    );
+
+    search(&game, &SmallRng::seed_from_u64(seed), u16::MAX)
+}
+
+/// Average of how many more points `strategy` scored than [`best_achievable_score`] over `seeds`,
+/// or zero for no seeds.
+///
+/// The oracle only plays games that remove something every round. A strategy that passes rerolls
+/// into rolls the oracle never sees, so it can beat the oracle on a seed, and its regret can come
+/// out negative.
+///
+/// # Panics
+///
+/// Panics if `game` starts with more than [`MAX_ORACLE_DICE`] dice.
+pub fn average_regret<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seeds: Range<u64>,
+) -> f64 {
+    let games = seeds.end.saturating_sub(seeds.start);
+    if games == 0 {
+        return 0.0;
+    }
+
+    let mut played = game.clone();
+    let mut total_regret: i64 = 0;
+
+    for i in seeds {
+        let points = play(strategy, &mut played, &mut SmallRng::seed_from_u64(i)).points;
+        total_regret += points as i64 - best_achievable_score(game, i) as i64;
+    }
+
+    total_regret as f64 / games as f64
+}
+
+/// Returns the exact chance that `strategy` finishes a game of `game` with zero points, for
+/// checking the gravies a simulation counts against what they should come to.
+///
+/// This walks every roll of every round, in the order [`simulate_game`](crate::simulate_game)
+/// would hand the dice to the strategy, passes included, so it only works out for a handful of
+/// dice. Only rounds that remove nothing but zeros are followed, since anything else already
+/// ruled out a gravy.
+///
+/// The walk assumes `strategy` always picks the same dice for the same board. A strategy that
+/// draws a new choice every time it's asked, like [`RandomStrategy`](crate::RandomStrategy), gets
+/// a number that doesn't mean anything.
+pub fn gravy_probability<S: Strategy + ?Sized>(strategy: &mut S, game: &Game) -> f64 {
+    let mut game = game.clone();
+    game.reset();
+    strategy.on_new_game();
+
+    gravy_chance(strategy, &game, 0, &mut HashMap::new())
+}
+
+// Chance of the rest of the game adding nothing, when the board holds `game`'s dice and the
+// strategy has passed `passes` times in a row. Boards are remembered by the faces in order, which
+// is all the strategy gets to see before the roll.
+fn gravy_chance<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    passes: u32,
+    seen: &mut HashMap<(Vec<Faces>, u32), f64>,
+) -> f64 {
+    if game.is_over() {
+        return 1.0;
+    }
+
+    let key = (game.dice.iter().map(|die| die.faces()).collect(), passes);
+    if let Some(&chance) = seen.get(&key) {
+        return chance;
+    }
+
+    let mut rolled = game.fork();
+    let mut points = vec![0; rolled.dice.len()];
+    let mut chance = 0.0;
+
+    loop {
+        for (die, &points) in rolled.dice.iter_mut().zip(&points) {
+            die.points = points;
+        }
+
+        let mut passes = passes;
+        let mut indices = select_dice(strategy, &rolled.dice, &mut passes);
+        if indices
+            .iter()
+            .all(|&i| rolled.removal_points(&rolled.dice[i]) == 0)
+        {
+            let mut next = rolled.fork();
+            next.remove_dice(&mut indices);
+            chance += gravy_chance(strategy, &next, passes, seen);
+        }
+
+        // Count through every roll like an odometer, the last die turning fastest
+        let faces = &key.0;
+        let model = game.roll_model();
+        match (0..points.len())
+            .rev()
+            .find(|&i| points[i] as u16 + 1 < model.outcomes(faces[i]))
+        {
+            Some(i) => {
+                points[i] += 1;
+                points[i + 1..].fill(0);
+            }
+            None => break,
+        }
+    }
+
+    let rolls: f64 = key
+        .0
+        .iter()
+        .map(|faces| game.roll_model().outcomes(*faces) as f64)
+        .product();
+    chance /= rolls;
+    seen.insert(key, chance);
+    chance
+}
+
+// Lowest number of points the rest of the game can add when the next roll comes from `rng`, or
+// `bound` if nothing comes in under it
+fn search(game: &Game, rng: &SmallRng, bound: u16) -> u16 {
+    if game.is_over() {
+        return 0;
+    }
+
+    let mut rng = rng.clone();
+    let mut rolled = game.fork();
+    rolled.roll_all(&mut rng);
+
+    // Taking everything ends the game right away, which every other removal has to beat
+    let dice = rolled.current_dice();
+    let all: u16 = dice.iter().map(|die| rolled.removal_points(die)).sum();
+    let mut best = bound.min(all);
+
+    for mask in 1..(1u32 << dice.len()) - 1 {
+        if best == 0 {
+            break;
+        }
+
+        let mut indices: Vec<usize> = (0..dice.len()).filter(|i| mask & (1 << i) != 0).collect();
+        let points: u16 = indices
+            .iter()
+            .map(|&i| rolled.removal_points(&dice[i]))
+            .sum();
+        if points >= best {
+            continue;
+        }
+
+        let mut next = rolled.fork();
+        next.remove_dice(&mut indices);
+        best = best.min(points + search(&next, &rng, best - points));
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{
+        FnStrategy, all_zero_or_big_min_strategy, fettermania_blackjack_strategy, one_max_strategy,
+        only_zero_strategy,
+    };
+    use crate::{MAX_CONSECUTIVE_PASSES, RollModel, all_strategies, run_simulations_on};
+
+    fn small_game() -> Game {
+        Game::with_composition(&[
+            (Faces::SIX, 2),
+            (Faces::EIGHT, 1),
+            (Faces::TEN, 1),
+            (Faces::TWELVE, 1),
+        ])
+    }
+
+    #[test]
+    fn test_oracle_beats_every_strategy() {
+        let game = small_game();
+
+        for seed in 0..200 {
+            let best = best_achievable_score(&game, seed);
+
+            for (name, mut strategy) in all_strategies() {
+                let mut played = game.clone();
+                let result = play(
+                    &mut strategy,
+                    &mut played,
+                    &mut SmallRng::seed_from_u64(seed),
+                );
+                assert!(
+                    best <= result.points,
+                    "{} scored {} on seed {}, under the oracle's {}",
+                    name,
+                    result.points,
+                    seed,
+                    best
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_oracle_single_die() {
+        // With one die there's nothing to choose, so the oracle scores whatever it rolls
+        let game = Game::with_composition(&[(Faces::TWELVE, 1)]);
+        for seed in 0..20 {
+            let mut played = game.clone();
+            let mut rng = SmallRng::seed_from_u64(seed);
+            played.roll_all(&mut rng);
+            let points = played.board_points() as u16;
+
+            assert_eq!(points, best_achievable_score(&game, seed));
+        }
+
+        assert_eq!(0, best_achievable_score(&Game::with_composition(&[]), 3));
+    }
+
+    #[test]
+    fn test_average_regret() {
+        let game = small_game();
+        let one_max = average_regret(&mut FnStrategy(one_max_strategy), &game, 0..100);
+        let fettermania = average_regret(
+            &mut FnStrategy(fettermania_blackjack_strategy),
+            &game,
+            0..100,
+        );
+
+        assert!(fettermania >= 0.0);
+        assert!(fettermania < one_max);
+
+        // Passing rerolls into rolls the oracle never tries, so waiting for zeros can come in
+        // under it
+        let only_zero = average_regret(&mut FnStrategy(only_zero_strategy), &game, 2..3);
+        assert_eq!(-15.0, only_zero);
+
+        assert_eq!(
+            0.0,
+            average_regret(&mut FnStrategy(one_max_strategy), &game, 5..5)
+        );
+    }
+
+    #[test]
+    fn test_gravy_probability_one_d6() {
+        let game = Game::with_composition(&[(Faces::SIX, 1)]);
+
+        // A strategy that always takes the die only gets a gravy when it rolls zero
+        let chance = gravy_probability(&mut FnStrategy(one_max_strategy), &game);
+        assert!((chance - 1.0 / 6.0).abs() < 1e-12);
+
+        // Waiting for a zero only misses if every roll up to the forced one comes up short
+        let chance = gravy_probability(&mut FnStrategy(only_zero_strategy), &game);
+        let misses = (5.0f64 / 6.0).powi(MAX_CONSECUTIVE_PASSES as i32 + 1);
+        assert!((chance - (1.0 - misses)).abs() < 1e-12);
+
+        // An extra face that can't score zero makes the first roll a gravy less often
+        let inclusive = game.clone().with_roll_model(RollModel::Inclusive);
+        let chance = gravy_probability(&mut FnStrategy(one_max_strategy), &inclusive);
+        assert!((chance - 1.0 / 7.0).abs() < 1e-12);
+
+        assert_eq!(
+            1.0,
+            gravy_probability(
+                &mut FnStrategy(one_max_strategy),
+                &Game::with_composition(&[])
+            )
+        );
+    }
+
+    #[test]
+    fn test_gravy_probability_matches_simulation() {
+        let game = Game::with_composition(&[(Faces::SIX, 2), (Faces::EIGHT, 1)]);
+        let mut strategy = FnStrategy(all_zero_or_big_min_strategy);
+        let chance = gravy_probability(&mut strategy, &game);
+
+        let summary = run_simulations_on(&mut strategy, &game, 0..20000);
+        let observed = summary.gravies as f64 / 20000.0;
+
+        // Well inside four standard errors of the exact chance
+        let error = (chance * (1.0 - chance) / 20000.0).sqrt();
+        assert!(
+            (observed - chance).abs() < 4.0 * error,
+            "observed {} against an exact {}",
+            observed,
+            chance
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the oracle only supports up to 8 dice")]
+    fn test_oracle_too_many_dice() {
+        best_achievable_score(&Game::new(), 0);
+    }
+}
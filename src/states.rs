@@ -0,0 +1,111 @@
+use crate::die::{Die, Faces};
+
+/// Iterator over every distinct board a set of dice can roll, see [`dice_states`].
+#[derive(Clone, Debug)]
+pub struct DiceStates {
+    // Sorted, so dice of the same size sit next to each other
+    faces: Vec<Faces>,
+    // Points of the next state to yield, never decreasing between dice of the same size so every
+    // multiset only comes up once. None once every state has been yielded.
+    points: Option<Vec<u8>>,
+}
+
+/// Enumerates every distinct board that rolling `faces` can produce, counting boards that only
+/// differ by which of two identical dice shows what as the same.
+///
+/// States are yielded lazily with the dice sorted by faces, so a strategy can be asked about each
+/// one without materializing them all. The count grows quickly: two d6s have 21 states, but the
+/// full default game has 5,940,480, so pass the subset of dice being studied. No dice at all has a
+/// single state, the empty board.
+pub fn dice_states(faces: &[Faces]) -> DiceStates {
+    let mut faces = faces.to_vec();
+    faces.sort();
+
+    DiceStates {
+        points: Some(vec![0; faces.len()]),
+        faces,
+    }
+}
+
+impl Iterator for DiceStates {
+    type Item = Vec<Die>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let points = self.points.as_mut()?;
+        let state = self
+            .faces
+            .iter()
+            .zip(points.iter())
+            .map(|(faces, points)| Die::with_faces(faces.value()).with_points(*points))
+            .collect();
+
+        // Bump the last die that can still go up, then reset everything after it to the lowest
+        // points that keep the same sized dice in order
+        match (0..points.len())
+            .rev()
+            .find(|&i| points[i] + 1 < self.faces[i].value())
+        {
+            Some(i) => {
+                points[i] += 1;
+                for j in i + 1..points.len() {
+                    points[j] = if self.faces[j] == self.faces[i] {
+                        points[i]
+                    } else {
+                        0
+                    };
+                }
+            }
+            None => self.points = None,
+        }
+
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn key(state: &[Die]) -> Vec<(u8, u8)> {
+        state
+            .iter()
+            .map(|die| (die.faces().value(), die.points()))
+            .collect()
+    }
+
+    #[test]
+    fn test_two_d6() {
+        let states: Vec<Vec<Die>> = dice_states(&[Faces::SIX, Faces::SIX]).collect();
+
+        // Multisets of 2 out of 6 point values: 7 choose 2
+        assert_eq!(21, states.len());
+        assert_eq!(
+            21,
+            states.iter().map(|s| key(s)).collect::<HashSet<_>>().len()
+        );
+        assert!(states.iter().all(|s| s[0].points() <= s[1].points()));
+    }
+
+    #[test]
+    fn test_mixed_dice() {
+        let states: Vec<Vec<Die>> =
+            dice_states(&[Faces::EIGHT, Faces::SIX, Faces::SIX, Faces::EIGHT]).collect();
+
+        // 21 for the d6s times 36 for the d8s
+        assert_eq!(21 * 36, states.len());
+        assert_eq!(
+            states.len(),
+            states.iter().map(|s| key(s)).collect::<HashSet<_>>().len()
+        );
+        assert_eq!(vec![(6, 0), (6, 0), (8, 0), (8, 0)], key(&states[0]));
+    }
+
+    #[test]
+    fn test_no_dice() {
+        assert_eq!(
+            vec![Vec::<(u8, u8)>::new()],
+            dice_states(&[]).map(|s| key(&s)).collect::<Vec<_>>()
+        );
+    }
+}
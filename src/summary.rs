@@ -0,0 +1,874 @@
+use crate::GameResult;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Statistics over the final scores of a range of simulated games.
+///
+/// Scores are the [points](crate::Die::points) taken off the board, as the game's
+/// [`ScoringMode`](crate::ScoringMode) adds them up, so every field here is in points and lower is
+/// better. None of them count [pips](crate::Die::pips).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationSummary {
+    /// How many games were played.
+    pub games: u64,
+    pub avg_points: f64,
+    /// Population standard deviation of the final scores.
+    pub std_dev: f64,
+    /// Standard error of `avg_points`: the sample standard deviation divided by the square root of
+    /// the number of games. Zero with fewer than two games, which have no spread to estimate it
+    /// from.
+    pub std_error: f64,
+    pub min_points: u16,
+    pub median: u16,
+    pub p90: u16,
+    pub p99: u16,
+    pub max_points: u16,
+    /// Seed of the first game that scored `min_points`, to replay it with
+    /// [`simulate_game`](crate::simulate_game).
+    pub best_seed: u64,
+    /// Seed of the first game that scored `max_points`.
+    pub worst_seed: u64,
+    /// Games that finished at or under the game's [gravy
+    /// threshold](crate::Game::with_gravy_threshold), zero points unless set otherwise.
+    pub gravies: u64,
+    /// Most gravies in a row, in seed order. This depends on which seeds were played, so it's only
+    /// comparable between runs over the same range.
+    pub longest_gravy_streak: u64,
+    /// Skewness of the final scores. Positive when the long tail is the bad games, and zero when
+    /// every game scored the same.
+    pub skewness: f64,
+    /// Excess kurtosis of the final scores, zero for a normal distribution or when every game
+    /// scored the same.
+    pub kurtosis: f64,
+    /// Average number of rounds a game took.
+    pub avg_rounds: f64,
+    /// Wall time spent simulating the games. Serialized as fractional milliseconds.
+    #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+    pub duration: Duration,
+}
+
+impl SimulationSummary {
+    // Dividing by no games would make most of these NaN, which sorts and prints badly
+    fn empty(duration: Duration) -> Self {
+        SimulationSummary {
+            games: 0,
+            avg_points: 0.0,
+            std_dev: 0.0,
+            std_error: 0.0,
+            min_points: 0,
+            median: 0,
+            p90: 0,
+            p99: 0,
+            max_points: 0,
+            best_seed: 0,
+            worst_seed: 0,
+            gravies: 0,
+            longest_gravy_streak: 0,
+            skewness: 0.0,
+            kurtosis: 0.0,
+            avg_rounds: 0.0,
+            duration,
+        }
+    }
+
+    /// The 95% confidence interval around `avg_points`, as `(low, high)`.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.std_error;
+        (self.avg_points - margin, self.avg_points + margin)
+    }
+
+    /// Combines the summaries of two runs over separate seeds.
+    ///
+    /// Averages, spreads, and higher moments are weighted by the number of games and come out the
+    /// same as one run over every seed, up to rounding. Two things can't be recovered from a
+    /// summary alone: percentiles are the weighted average of both runs' percentiles, which is
+    /// only exact when they agree, and the longest gravy streak misses a streak running across
+    /// the boundary between the two runs. Ties on the best and worst games go to the lower seed,
+    /// and the durations are added up. A run of no games adds nothing but its duration.
+    pub fn merge(&self, other: &Self) -> Self {
+        let (n_self, n_other) = (self.games, other.games);
+        // An empty run's zeroed minimum and seeds aren't games, so they mustn't win the best game
+        if n_self == 0 || n_other == 0 {
+            let played = if n_self == 0 { other } else { self };
+            return SimulationSummary {
+                duration: self.duration + other.duration,
+                ..played.clone()
+            };
+        }
+
+        let mut moments = Moments::from_summary(self);
+        moments.merge(&Moments::from_summary(other));
+        let games = n_self + n_other;
+
+        let weighted = |a: f64, b: f64| (a * n_self as f64 + b * n_other as f64) / games as f64;
+        let percentile = |a: u16, b: u16| weighted(a as f64, b as f64).round() as u16;
+        let best = (self.min_points, self.best_seed).min((other.min_points, other.best_seed));
+        let worst = [
+            (self.max_points, self.worst_seed),
+            (other.max_points, other.worst_seed),
+        ]
+        .into_iter()
+        .min_by_key(|&(points, seed)| (std::cmp::Reverse(points), seed))
+        .unwrap();
+
+        SimulationSummary {
+            games,
+            avg_points: moments.mean(),
+            std_dev: moments.variance().sqrt(),
+            std_error: std_error(moments.variance(), games),
+            min_points: best.0,
+            median: percentile(self.median, other.median),
+            p90: percentile(self.p90, other.p90),
+            p99: percentile(self.p99, other.p99),
+            max_points: worst.0,
+            best_seed: best.1,
+            worst_seed: worst.1,
+            gravies: self.gravies + other.gravies,
+            longest_gravy_streak: self.longest_gravy_streak.max(other.longest_gravy_streak),
+            skewness: shape(&moments).0,
+            kurtosis: shape(&moments).1,
+            avg_rounds: weighted(self.avg_rounds, other.avg_rounds),
+            duration: self.duration + other.duration,
+        }
+    }
+}
+
+// Standard error of the average of `games` scores with population variance `variance`, or zero
+// when fewer than two games leave no spread to estimate it from. NaN would serialize as null.
+fn std_error(variance: f64, games: u64) -> f64 {
+    if games < 2 {
+        return 0.0;
+    }
+
+    let sample_variance = variance * games as f64 / (games as f64 - 1.0);
+    (sample_variance / games as f64).sqrt()
+}
+
+// Skewness and excess kurtosis, or zeros when every game scored the same and there's no spread
+// for them to describe
+fn shape(moments: &Moments) -> (f64, f64) {
+    if moments.variance() > 0.0 {
+        (moments.skewness(), moments.kurtosis())
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Ranks a summary by its average with a bonus for gravies, for players who would give up some
+/// points on average for more perfect games. Lower is better, like the average itself.
+///
+/// Every percent of games that ended in a gravy takes `gravy_weight` points off the average, so a
+/// weight of zero ranks by average alone.
+pub fn score_value(summary: &SimulationSummary, gravy_weight: f64) -> f64 {
+    if summary.games == 0 {
+        return 0.0;
+    }
+
+    let gravy_percent = 100.0 * summary.gravies as f64 / summary.games as f64;
+    summary.avg_points - gravy_weight * gravy_percent
+}
+
+/// How many games it takes for the `confidence` interval around an average to reach `margin`
+/// points either side, if the scores have a standard deviation of `estimated_std_dev`.
+///
+/// Uses the normal approximation `n = (z * std_dev / margin)^2`, where `z` is the two-sided
+/// critical value for `confidence`, e.g. about 1.96 for 0.95. The standard deviation of a
+/// strategy's scores can come from a smaller pilot run, see
+/// [`recommend_simulations`](crate::recommend_simulations).
+///
+/// # Panics
+///
+/// Panics if `margin` isn't positive, `confidence` isn't strictly between 0 and 1, or
+/// `estimated_std_dev` is negative.
+pub fn sample_size_for(margin: f64, confidence: f64, estimated_std_dev: f64) -> u64 {
+    assert!(margin > 0.0, "margin must be positive");
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "confidence must be between 0 and 1"
+    );
+    assert!(
+        estimated_std_dev >= 0.0,
+        "standard deviation can't be negative"
+    );
+
+    let z = normal_quantile((1.0 + confidence) / 2.0);
+    (z * estimated_std_dev / margin).powi(2).ceil() as u64
+}
+
+// Inverse of the standard normal CDF, from Acklam's rational approximation, which is within about
+// 1e-9 of the true value over all of (0, 1)
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let polynomial =
+        |coefficients: &[f64], x: f64| coefficients.iter().fold(0.0, |acc, c| acc * x + c);
+    // The tails share coefficients, mirrored around the middle
+    let tail = |q: f64| polynomial(&C, q) / (polynomial(&D, q) * q + 1.0);
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        polynomial(&A, r) * q / (polynomial(&B, r) * r + 1.0)
+    }
+}
+
+/// Streaming accumulator for the first four moments of a distribution, so they can be computed
+/// in one pass without keeping the samples around.
+///
+/// Uses Welford's update, extended to the third and fourth moments, to stay numerically stable
+/// over hundreds of millions of samples. Accumulators over separate samples can be merged.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Moments {
+    count: u64,
+    mean: f64,
+    // Sums of the 2nd, 3rd, and 4th powers of the deviations from the mean
+    m2: f64,
+    m3: f64,
+    m4: f64,
+}
+
+impl Moments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, x: f64) {
+        self.add_repeated(x, 1);
+    }
+
+    /// Adds `count` samples of `x` at once.
+    pub fn add_repeated(&mut self, x: f64, count: u64) {
+        self.merge(&Moments {
+            count,
+            mean: x,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+        });
+    }
+
+    /// Combines the samples of `other` into these.
+    pub fn merge(&mut self, other: &Moments) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (a, b) = (self.count as f64, other.count as f64);
+        let n = a + b;
+        let delta = other.mean - self.mean;
+        let delta_n = delta / n;
+
+        let m2 = self.m2 + other.m2 + delta * delta_n * a * b;
+        let m3 = self.m3
+            + other.m3
+            + delta * delta_n * delta_n * a * b * (a - b)
+            + 3.0 * delta_n * (a * other.m2 - b * self.m2);
+        let m4 = self.m4
+            + other.m4
+            + delta * delta_n.powi(3) * a * b * (a * a - a * b + b * b)
+            + 6.0 * delta_n * delta_n * (a * a * other.m2 + b * b * self.m2)
+            + 4.0 * delta_n * (a * other.m3 - b * self.m3);
+
+        self.count += other.count;
+        self.mean += delta_n * b;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+    }
+
+    // Rebuilds the accumulator a summary was computed from
+    fn from_summary(summary: &SimulationSummary) -> Self {
+        let count = summary.games;
+        let n = count as f64;
+        let m2 = summary.std_dev * summary.std_dev * n;
+        // Every game scored the same, so there are no higher moments to speak of
+        let (m3, m4) = if m2 > 0.0 {
+            (
+                summary.skewness * m2.powf(1.5) / n.sqrt(),
+                (summary.kurtosis + 3.0) * m2 * m2 / n,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        Moments {
+            count,
+            mean: summary.avg_points,
+            m2,
+            m3,
+            m4,
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance.
+    pub fn variance(&self) -> f64 {
+        self.m2 / self.count as f64
+    }
+
+    pub fn skewness(&self) -> f64 {
+        (self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5)
+    }
+
+    /// Excess kurtosis, which is zero for a normal distribution.
+    pub fn kurtosis(&self) -> f64 {
+        self.count as f64 * self.m4 / (self.m2 * self.m2) - 3.0
+    }
+}
+
+#[cfg(feature = "serde")]
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let ms = f64::deserialize(deserializer)?;
+        Ok(Duration::from_nanos((ms * 1_000_000.0).round() as u64))
+    }
+}
+
+// Frequency table of final scores, indexed by points. Scores are small, so this stays tiny no
+// matter how many games are played.
+//
+// Seeds are u64, so a sweep plays at most u64::MAX games, and every counter that goes up by at
+// most one per game (games, each score's count, gravies and their streaks) can't overflow. Rounds
+// can go up by hundreds per game, so they're summed in a u128.
+pub(crate) struct Totals {
+    counts: Vec<u64>,
+    games: u64,
+    rounds: u128,
+    // (points, seed) of the lowest and highest scoring games, keeping the first seed on ties
+    best: Option<(u16, u64)>,
+    worst: Option<(u16, u64)>,
+    // Gravy runs at the start and end of the games played so far, so runs that cross a parallel
+    // split can be stitched back together
+    leading_gravies: u64,
+    trailing_gravies: u64,
+    longest_gravy_streak: u64,
+    // Most points a game can score and still count as a gravy
+    gravy_threshold: u16,
+}
+
+impl Totals {
+    pub(crate) fn new() -> Self {
+        Self::with_gravy_threshold(0)
+    }
+
+    pub(crate) fn with_gravy_threshold(gravy_threshold: u16) -> Self {
+        Totals {
+            counts: Vec::new(),
+            games: 0,
+            rounds: 0,
+            best: None,
+            worst: None,
+            leading_gravies: 0,
+            trailing_gravies: 0,
+            longest_gravy_streak: 0,
+            gravy_threshold,
+        }
+    }
+
+    pub(crate) fn add(&mut self, seed: u64, result: GameResult) {
+        let GameResult { points, rounds } = result;
+        self.rounds += rounds as u128;
+        let gravy = points <= self.gravy_threshold;
+
+        let index = points as usize;
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+
+        if gravy {
+            if self.leading_gravies == self.games {
+                self.leading_gravies += 1;
+            }
+            self.trailing_gravies += 1;
+            self.longest_gravy_streak = self.longest_gravy_streak.max(self.trailing_gravies);
+        } else {
+            self.trailing_gravies = 0;
+        }
+        self.games += 1;
+
+        if self.best.is_none_or(|(best, _)| points < best) {
+            self.best = Some((points, seed));
+        }
+        if self.worst.is_none_or(|(worst, _)| points > worst) {
+            self.worst = Some((points, seed));
+        }
+    }
+
+    // `other` must hold the games played right after the ones in `self`
+    #[cfg(feature = "rayon")]
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        self.longest_gravy_streak = self
+            .longest_gravy_streak
+            .max(other.longest_gravy_streak)
+            .max(self.trailing_gravies + other.leading_gravies);
+        if self.leading_gravies == self.games {
+            self.leading_gravies += other.leading_gravies;
+        }
+        if other.trailing_gravies == other.games {
+            self.trailing_gravies += other.games;
+        } else {
+            self.trailing_gravies = other.trailing_gravies;
+        }
+        self.games += other.games;
+        self.rounds += other.rounds;
+
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts) {
+            *count += other_count;
+        }
+
+        // Seeds are played in order, so the first game on a tie is the lower seed
+        self.best = self.best.into_iter().chain(other.best).min();
+        self.worst = self
+            .worst
+            .into_iter()
+            .chain(other.worst)
+            .min_by_key(|&(points, seed)| (std::cmp::Reverse(points), seed));
+        self
+    }
+
+    // Nearest-rank percentile: the smallest score that at least `p` of the games came in under
+    fn percentile(&self, games: u64, p: f64) -> u16 {
+        let rank = ((p * games as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        for (points, count) in self.scores() {
+            seen += count;
+            if seen >= rank {
+                return points;
+            }
+        }
+        0
+    }
+
+    fn scores(&self) -> impl Iterator<Item = (u16, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(points, count)| (points as u16, *count))
+    }
+
+    pub(crate) fn histogram(&self) -> BTreeMap<u16, u64> {
+        self.scores().collect()
+    }
+
+    pub(crate) fn finish(&self, duration: Duration) -> SimulationSummary {
+        let games = self.games;
+        if games == 0 {
+            return SimulationSummary::empty(duration);
+        }
+
+        // Every game with the same score adds the same sample, so feeding the moments from the
+        // frequency table is one pass over a few dozen entries however many games were played,
+        // and comes out the same whichever way a parallel run was split up
+        let mut moments = Moments::new();
+        for (points, count) in self.scores() {
+            moments.add_repeated(points as f64, count);
+        }
+
+        SimulationSummary {
+            games,
+            avg_points: moments.mean(),
+            std_dev: moments.variance().sqrt(),
+            std_error: std_error(moments.variance(), games),
+            min_points: self.scores().next().map_or(u16::MAX, |(p, _)| p),
+            median: self.percentile(games, 0.5),
+            p90: self.percentile(games, 0.9),
+            p99: self.percentile(games, 0.99),
+            max_points: self.scores().last().map_or(0, |(p, _)| p),
+            best_seed: self.best.map_or(0, |(_, seed)| seed),
+            worst_seed: self.worst.map_or(0, |(_, seed)| seed),
+            gravies: self
+                .counts
+                .iter()
+                .take(self.gravy_threshold as usize + 1)
+                .sum(),
+            longest_gravy_streak: self.longest_gravy_streak,
+            skewness: shape(&moments).0,
+            kurtosis: shape(&moments).1,
+            avg_rounds: self.rounds as f64 / games as f64,
+            duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulate_game;
+    use crate::strategy::{FnStrategy, fettermania_blackjack_strategy};
+
+    fn game(points: u16) -> GameResult {
+        GameResult { points, rounds: 15 }
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut totals = Totals::new();
+        for (seed, points) in (10..).zip([5, 2, 9, 4, 4, 7, 4, 5]) {
+            totals.add(seed, game(points));
+        }
+
+        let summary = totals.finish(Duration::ZERO);
+        assert_eq!(summary.avg_points, 5.0);
+        assert_eq!(summary.std_dev, 2.0);
+        assert_eq!(summary.std_error, (32.0f64 / 7.0 / 8.0).sqrt());
+        assert_eq!(summary.min_points, 2);
+        assert_eq!(summary.median, 4); // sorted: 2 4 4 4 5 5 7 9
+        assert_eq!(summary.p90, 9);
+        assert_eq!(summary.p99, 9);
+        assert_eq!(summary.max_points, 9);
+        assert_eq!(summary.best_seed, 11);
+        assert_eq!(summary.worst_seed, 12);
+        assert_eq!(summary.gravies, 0);
+        assert_eq!(summary.avg_rounds, 15.0);
+    }
+
+    #[test]
+    fn test_finish_no_games() {
+        let empty = Totals::new().finish(Duration::from_millis(1));
+        assert_eq!(0, empty.games);
+        assert_eq!(
+            (0.0, 0.0, 0.0),
+            (empty.avg_points, empty.std_dev, empty.avg_rounds)
+        );
+        assert_eq!((0, 0), (empty.min_points, empty.max_points));
+        assert_eq!(0.0, empty.std_error);
+        assert_eq!(0.0, score_value(&empty, 1.0));
+
+        // Merging an empty run keeps the other one's games
+        let mut totals = Totals::new();
+        totals.add(7, game(3));
+        let played = totals.finish(Duration::from_millis(2));
+        assert_eq!(
+            (0.0, 0.0, 0.0),
+            (played.std_error, played.skewness, played.kurtosis)
+        );
+        for merged in [empty.merge(&played), played.merge(&empty)] {
+            assert_eq!((3, 7), (merged.min_points, merged.best_seed));
+            assert_eq!(3.0, merged.avg_points);
+            assert_eq!(Duration::from_millis(3), merged.duration);
+        }
+    }
+
+    #[test]
+    fn test_totals_near_overflow() {
+        let mut totals = Totals::new();
+        totals.counts = vec![u64::MAX - 2, 0, 1];
+        totals.games = u64::MAX - 1;
+        totals.rounds = u64::MAX as u128 - 5;
+        totals.leading_gravies = u64::MAX - 2;
+        totals.trailing_gravies = u64::MAX - 2;
+        totals.longest_gravy_streak = u64::MAX - 2;
+
+        // Seed u64::MAX - 1 is the last one a sweep over 0..u64::MAX can play
+        totals.add(u64::MAX - 1, game(0));
+        assert_eq!(u64::MAX, totals.games);
+        assert_eq!(u64::MAX - 1, totals.counts[0]);
+        assert_eq!(u64::MAX - 1, totals.longest_gravy_streak);
+        assert_eq!(u64::MAX as u128 + 10, totals.rounds);
+
+        let summary = totals.finish(Duration::ZERO);
+        assert_eq!(u64::MAX, summary.games);
+        assert_eq!(u64::MAX - 1, summary.gravies);
+        assert!((summary.avg_rounds - 1.0).abs() < 1e-9);
+
+        // Merging the rounds of two huge halves of a parallel run
+        #[cfg(feature = "rayon")]
+        {
+            let half = || {
+                let mut totals = Totals::new();
+                totals.add(0, game(3));
+                totals.rounds = u64::MAX as u128;
+                totals
+            };
+            let merged = half().merge(half());
+            assert_eq!(2 * u64::MAX as u128, merged.rounds);
+        }
+    }
+
+    #[test]
+    fn test_extreme_seed_ties() {
+        let mut totals = Totals::new();
+        for (seed, points) in (0..).zip([3, 0, 8, 0, 8, 5]) {
+            totals.add(seed, game(points));
+        }
+
+        // Ties keep the first game
+        let summary = totals.finish(Duration::ZERO);
+        assert_eq!(summary.best_seed, 1);
+        assert_eq!(summary.worst_seed, 2);
+    }
+
+    #[test]
+    fn test_moments() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+        let scores: Vec<f64> = (0..500)
+            .map(|seed| simulate_game(&mut strategy, seed) as f64)
+            .collect();
+
+        // Naive two passes over every score
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let central = |k| scores.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+        let variance = central(2);
+
+        let mut streaming = Moments::new();
+        let (mut first, mut second) = (Moments::new(), Moments::new());
+        for (i, x) in scores.iter().enumerate() {
+            streaming.add(*x);
+            if i < 200 {
+                first.add(*x)
+            } else {
+                second.add(*x)
+            }
+        }
+        first.merge(&second);
+
+        for moments in [streaming, first] {
+            assert_eq!(500, moments.count());
+            assert!((moments.mean() - mean).abs() < 1e-12);
+            assert!((moments.variance() - variance).abs() < 1e-10);
+            assert!((moments.skewness() - central(3) / variance.powf(1.5)).abs() < 1e-10);
+            assert!(
+                (moments.kurtosis() - (central(4) / (variance * variance) - 3.0)).abs() < 1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn test_moments_symmetric() {
+        let mut moments = Moments::new();
+        for x in [1.0, 2.0, 3.0, 4.0] {
+            moments.add(x);
+        }
+
+        assert_eq!(2.5, moments.mean());
+        assert_eq!(1.25, moments.variance());
+        assert_eq!(0.0, moments.skewness());
+        // Uniform over four points: 2.5625 / 1.5625 - 3
+        assert!((moments.kurtosis() + 1.36).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_longest_gravy_streak() {
+        let mut totals = Totals::new();
+        for (seed, points) in (0..).zip([0, 0, 4, 0, 0, 0, 2, 0]) {
+            totals.add(seed, game(points));
+        }
+
+        let summary = totals.finish(Duration::ZERO);
+        assert_eq!(summary.gravies, 6);
+        assert_eq!(summary.longest_gravy_streak, 3);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_merge_gravy_streaks() {
+        let scores = [0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 0, 1, 0];
+
+        let mut whole = Totals::new();
+        for (seed, points) in (0..).zip(scores) {
+            whole.add(seed, game(points));
+        }
+
+        // Every way of splitting the games in three has to stitch the same streaks back together
+        for i in 0..=scores.len() {
+            for j in i..=scores.len() {
+                let parts = [0..i, i..j, j..scores.len()].map(|range| {
+                    let mut totals = Totals::new();
+                    for seed in range {
+                        totals.add(seed as u64, game(scores[seed]));
+                    }
+                    totals
+                });
+
+                let merged = parts.into_iter().reduce(Totals::merge).unwrap();
+                assert_eq!(
+                    whole.finish(Duration::ZERO),
+                    merged.finish(Duration::ZERO),
+                    "split at {} and {}",
+                    i,
+                    j
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_summary_merge() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+        let whole = crate::run_simulations_range(&mut strategy, 0..30_000);
+        let first = crate::run_simulations_range(&mut strategy, 0..10_000);
+        let second = crate::run_simulations_range(&mut strategy, 10_000..30_000);
+
+        let merged = first.merge(&second);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        assert!(close(whole.avg_points, merged.avg_points));
+        assert!(close(whole.std_dev, merged.std_dev));
+        assert!(close(whole.std_error, merged.std_error));
+        assert!(close(whole.skewness, merged.skewness));
+        assert!(close(whole.kurtosis, merged.kurtosis));
+        assert!(close(whole.avg_rounds, merged.avg_rounds));
+
+        // Counts and extremes come out exactly as one run would have them
+        assert_eq!(whole.games, merged.games);
+        assert_eq!(whole.gravies, merged.gravies);
+        assert_eq!(
+            (whole.min_points, whole.best_seed),
+            (merged.min_points, merged.best_seed)
+        );
+        assert_eq!(
+            (whole.max_points, whole.worst_seed),
+            (merged.max_points, merged.worst_seed)
+        );
+        assert_eq!(first.duration + second.duration, merged.duration);
+
+        // Percentiles are only blended, so all that's certain is they land between both runs'
+        let between = |merged: u16, a: u16, b: u16| a.min(b) <= merged && merged <= a.max(b);
+        assert!(between(merged.median, first.median, second.median));
+        assert!(between(merged.p90, first.p90, second.p90));
+        assert!(between(merged.p99, first.p99, second.p99));
+
+        // A streak across the boundary is missed, so the merged streak can only fall short
+        let streak = first.longest_gravy_streak.max(second.longest_gravy_streak);
+        assert_eq!(streak, merged.longest_gravy_streak);
+        assert!(merged.longest_gravy_streak <= whole.longest_gravy_streak);
+
+        // Which run comes first doesn't matter
+        assert_eq!(merged, second.merge(&first));
+    }
+
+    #[test]
+    fn test_normal_quantile() {
+        assert_eq!(0.0, normal_quantile(0.5));
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.995) - 2.575829).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326348).abs() < 1e-6);
+        assert!((normal_quantile(0.001) + normal_quantile(0.999)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_size_for() {
+        // (1.959964 * 4 / 0.05)^2 = 24585.3, so the next whole game
+        assert_eq!(24586, sample_size_for(0.05, 0.95, 4.0));
+        // (2.575829 * 4 / 0.05)^2 = 42463.3
+        assert_eq!(42464, sample_size_for(0.05, 0.99, 4.0));
+        // Halving the margin takes four times the games
+        assert_eq!(98342, sample_size_for(0.025, 0.95, 4.0));
+
+        assert_eq!(0, sample_size_for(0.1, 0.95, 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "confidence must be between 0 and 1")]
+    fn test_sample_size_for_certainty() {
+        sample_size_for(0.05, 1.0, 4.0);
+    }
+
+    #[test]
+    fn test_score_value() {
+        let summarize = |scores: [u16; 4]| {
+            let mut totals = Totals::new();
+            for (seed, points) in (0..).zip(scores) {
+                totals.add(seed, game(points));
+            }
+            totals.finish(Duration::ZERO)
+        };
+        let gravies = summarize([0, 0, 10, 14]);
+        // Better on average, but never a gravy
+        let steady = summarize([5, 5, 5, 6]);
+
+        assert_eq!(6.0, score_value(&gravies, 0.0));
+        assert_eq!(5.25, score_value(&steady, 0.0));
+        // Half the games were gravies, so every unit of weight is worth 50 points
+        assert_eq!(-44.0, score_value(&gravies, 1.0));
+        assert!(score_value(&gravies, 0.1) < score_value(&steady, 0.1));
+        assert!(score_value(&gravies, 0.01) > score_value(&steady, 0.01));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut totals = Totals::new();
+        for (seed, points) in (0..).zip([0, 3, 8, 12, 30]) {
+            totals.add(seed, game(points));
+        }
+        let summary = totals.finish(Duration::from_micros(105_330));
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(json.contains(r#""duration_ms":105.33"#));
+
+        let parsed: SimulationSummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(summary, parsed);
+
+        // Runs too short to have a spread still read back in
+        let mut one = Totals::new();
+        one.add(0, game(4));
+        for summary in [
+            Totals::new().finish(Duration::ZERO),
+            one.finish(Duration::ZERO),
+        ] {
+            let json = serde_json::to_string(&summary).unwrap();
+            assert!(!json.contains("null"), "{}", json);
+            assert_eq!(summary, serde_json::from_str(&json).unwrap());
+        }
+    }
+}
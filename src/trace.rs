@@ -0,0 +1,167 @@
+use crate::die::Die;
+use crate::game::Game;
+use crate::select_dice;
+use crate::strategy::Strategy;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+/// What happened in a single round of a traced game.
+#[derive(Clone, Debug)]
+pub struct RoundRecord {
+    /// The board right after rolling, before anything was removed.
+    pub dice: Vec<Die>,
+    /// Indices into `dice` that the strategy removed, empty if it passed.
+    pub removed: Vec<usize>,
+    /// Points added to the total by the removed dice.
+    pub points: u16,
+}
+
+/// Round-by-round history of a game, for debugging strategies.
+#[derive(Clone, Debug, Default)]
+pub struct GameTrace {
+    pub rounds: Vec<RoundRecord>,
+}
+
+impl GameTrace {
+    pub fn total_points(&self) -> u16 {
+        self.rounds.iter().map(|round| round.points).sum()
+    }
+}
+
+/// Plays the same game as [`simulate_game`](crate::simulate_game), but records every round.
+pub fn simulate_game_traced<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> GameTrace {
+    simulate_game_traced_on(strategy, &Game::new(), seed)
+}
+
+/// Like [`simulate_game_traced`], but the game starts from the dice `game` started with and
+/// follows its rules.
+pub fn simulate_game_traced_on<S: Strategy + ?Sized>(
+    strategy: &mut S,
+    game: &Game,
+    seed: u64,
+) -> GameTrace {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut game = game.clone();
+    game.reset();
+    let mut trace = GameTrace::default();
+    let mut passes = 0;
+    strategy.on_new_game();
+
+    while !game.is_over() {
+        game.roll_all(&mut rng);
+        let dice = game.dice.clone();
+        let mut removed = select_dice(strategy, &game.dice, &mut passes);
+        let points = game.remove_dice(&mut removed);
+
+        trace.rounds.push(RoundRecord {
+            dice,
+            removed,
+            points,
+        });
+    }
+
+    trace
+}
+
+/// Plays the same game as [`simulate_game`](crate::simulate_game), printing the board before
+/// every removal along with the dice removed and the running total. Returns the final score.
+///
+/// Dice that aren't d6s are marked the same way as `{:#}` on a [`Game`].
+pub fn replay_game<S: Strategy + ?Sized>(strategy: &mut S, seed: u64) -> u16 {
+    replay_game_on(strategy, &Game::new(), seed)
+}
+
+/// Like [`replay_game`], but the game starts from the dice `game` started with and follows its
+/// rules.
+pub fn replay_game_on<S: Strategy + ?Sized>(strategy: &mut S, game: &Game, seed: u64) -> u16 {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut game = game.clone();
+    game.reset();
+    let mut passes = 0;
+    let mut round = 0;
+    strategy.on_new_game();
+
+    while !game.is_over() {
+        round += 1;
+        game.roll_all(&mut rng);
+        println!("Round {}:\n{:#}", round, game);
+
+        let mut removed = select_dice(strategy, &game.dice, &mut passes);
+        let dice: Vec<String> = removed.iter().map(|&i| game.dice[i].to_string()).collect();
+        let points = game.remove_dice(&mut removed);
+
+        if removed.is_empty() {
+            println!("Passed, {} total\n", game.score());
+        } else {
+            println!(
+                "Removed {} for {} points, {} total\n",
+                dice.join(" "),
+                points,
+                game.score()
+            );
+        }
+    }
+
+    println!("Final score: {}", game.score());
+    game.score()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{FnStrategy, fettermania_blackjack_strategy};
+    use crate::{Faces, RollModel, run_simulations_on, simulate_game};
+
+    #[test]
+    fn test_replay_game() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        for seed in [0, 3, 800] {
+            assert_eq!(
+                simulate_game(&mut strategy, seed),
+                replay_game(&mut strategy, seed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_simulate_game_traced() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+
+        for seed in 0..100 {
+            let trace = simulate_game_traced(&mut strategy, seed);
+
+            assert_eq!(simulate_game(&mut strategy, seed), trace.total_points());
+            assert_eq!(
+                15,
+                trace.rounds.iter().map(|r| r.removed.len()).sum::<usize>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_traced_on() {
+        let mut strategy = FnStrategy(fettermania_blackjack_strategy);
+        let game = Game::with_composition(&[(Faces::SIX, 3), (Faces::TWELVE, 1)])
+            .with_roll_model(RollModel::Inclusive);
+
+        for seed in 0..50 {
+            let trace = simulate_game_traced_on(&mut strategy, &game, seed);
+            let played = run_simulations_on(&mut strategy, &game, seed..seed + 1);
+
+            assert_eq!(played.avg_points, trace.total_points() as f64);
+            assert_eq!(
+                played.avg_points,
+                replay_game_on(&mut strategy, &game, seed) as f64
+            );
+            assert_eq!(4, trace.rounds[0].dice.len());
+        }
+
+        // Only the inclusive model lets a d6 show 6 points
+        let top = (0..200)
+            .flat_map(|seed| simulate_game_traced_on(&mut strategy, &game, seed).rounds)
+            .flat_map(|round| round.dice)
+            .any(|die| die.faces() == Faces::SIX && die.points() == 6);
+        assert!(top);
+    }
+}